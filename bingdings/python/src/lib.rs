@@ -224,9 +224,10 @@ impl SolorDate {
 
     /// 计算四柱
     ///
-    /// 说明：是按照东八区算的
-    pub fn sizhu(&self, t: f64) -> PyResult<(GanZhi, GanZhi, GanZhi, GanZhi)> {
-        let (y, m, d, t) = self.0.sizhu(t);
+    /// 说明：t是东八区时间，传入出生地经度lon(弧度)时日柱、时柱按真太阳时计算
+    #[pyo3(signature = (t, lon=None))]
+    pub fn sizhu(&self, t: f64, lon: Option<f64>) -> PyResult<(GanZhi, GanZhi, GanZhi, GanZhi)> {
+        let (y, m, d, t) = self.0.sizhu_with_lon(t, lon);
         Ok((GanZhi::from(y), GanZhi::from(m), GanZhi::from(d), GanZhi::from(t)))
     }
 }
@@ -415,6 +416,20 @@ pub fn so_accurate2(jd: f64) -> PyResult<f64> {
     Ok(reph::lunnar::so_accurate2(jd))
 }
 
+/// 计算第k个朔望月的月相时刻
+///
+/// phase: 0:朔， 1:上弦， 2:望， 3:下弦
+#[pyfunction]
+pub fn moon_phase(k: i32, phase: usize) -> PyResult<f64> {
+    Ok(reph::lunnar::moon_phase(k, reph::lunnar::MoonPhase::from(phase)))
+}
+
+/// 儒略日之前最近一次朔的月相序数
+#[pyfunction]
+pub fn lunation_number(jd: f64) -> PyResult<i32> {
+    Ok(reph::lunnar::lunation_number(jd))
+}
+
 // 天文历以及星座计算
 
 /// 星体
@@ -432,6 +447,12 @@ pub enum CelestialBody {
     Pluto,
     Sun,
     Moon,
+    MeanRahu,
+    TrueRahu,
+    MeanKetu,
+    TrueKetu,
+    MeanYuebei,
+    TrueYuebei,
 }
 
 impl From<CelestialBody> for reph::astronomy::CelestialBody {
@@ -448,6 +469,12 @@ impl From<CelestialBody> for reph::astronomy::CelestialBody {
             CelestialBody::Pluto => Self::Pluto,
             CelestialBody::Sun => Self::Sun,
             CelestialBody::Moon => Self::Moon,
+            CelestialBody::MeanRahu => Self::MeanRahu,
+            CelestialBody::TrueRahu => Self::TrueRahu,
+            CelestialBody::MeanKetu => Self::MeanKetu,
+            CelestialBody::TrueKetu => Self::TrueKetu,
+            CelestialBody::MeanYuebei => Self::MeanYuebei,
+            CelestialBody::TrueYuebei => Self::TrueYuebei,
         }
     }
 }
@@ -466,6 +493,12 @@ impl From<reph::astronomy::CelestialBody> for CelestialBody {
             reph::astronomy::CelestialBody::Pluto => Self::Pluto,
             reph::astronomy::CelestialBody::Sun => Self::Sun,
             reph::astronomy::CelestialBody::Moon => Self::Moon,
+            reph::astronomy::CelestialBody::MeanRahu => Self::MeanRahu,
+            reph::astronomy::CelestialBody::TrueRahu => Self::TrueRahu,
+            reph::astronomy::CelestialBody::MeanKetu => Self::MeanKetu,
+            reph::astronomy::CelestialBody::TrueKetu => Self::TrueKetu,
+            reph::astronomy::CelestialBody::MeanYuebei => Self::MeanYuebei,
+            reph::astronomy::CelestialBody::TrueYuebei => Self::TrueYuebei,
         }
     }
 }
@@ -553,6 +586,22 @@ impl PlanetCoordinates {
     pub fn body(&self) -> PyResult<CelestialBody> {
         Ok(CelestialBody::from(self.0.body))
     }
+    #[getter]
+    pub fn lon_speed(&self) -> PyResult<f64> {
+        Ok(self.0.lon_speed)
+    }
+    #[getter]
+    pub fn lat_speed(&self) -> PyResult<f64> {
+        Ok(self.0.lat_speed)
+    }
+    #[getter]
+    pub fn dist_speed(&self) -> PyResult<f64> {
+        Ok(self.0.dist_speed)
+    }
+    #[getter]
+    pub fn retrograde(&self) -> PyResult<bool> {
+        Ok(self.0.retrograde)
+    }
 
     fn __str__(&self)->PyResult<String>{
         Ok(format!("{}", self.0))
@@ -560,6 +609,10 @@ impl PlanetCoordinates {
 }
 
 
+/// 宫位盘
+///
+/// Hourse(jd, tz, lon, lat)：jd为世界时儒略日，tz仅作记录，lon东经为负，弧度制；
+/// 按本地时间、东经为正构造用Hourse.from_local(jd, tz, lon, lat)
 #[derive(Debug)]
 #[pyclass]
 pub struct Hourse(reph::astronomy::Hourse);
@@ -572,11 +625,20 @@ impl From<reph::astronomy::Hourse> for Hourse{
 
 #[pymethods]
 impl Hourse{
+    /// jd为世界时儒略日，lon东经为负，弧度制
     #[new(text_signature="(jd,tz,lon,lat)")]
     pub fn new(jd: f64, tz: f64, lon: f64, lat: f64)->PyResult<Self>{
         Ok(Self(reph::astronomy::Hourse::new(jd, tz, lon, lat)))
     }
 
+    /// jd为本地时间儒略日，tz为时区(东八区为-8)，lon东经为正，弧度制
+    #[staticmethod]
+    #[pyo3(text_signature="(jd,tz,lon,lat)")]
+    pub fn from_local(jd: f64, tz: f64, lon: f64, lat: f64)->PyResult<Self>{
+        Ok(Self(reph::astronomy::Hourse::from_local(jd, tz, lon, lat)))
+    }
+
+    /// J1900起算的儒略世纪，世界时
     #[getter]
     fn t(& mut self) -> PyResult<f64>{
         Ok(self.0.t())
@@ -601,6 +663,34 @@ impl Hourse{
     pub fn ep(& mut self) -> PyResult<f64>{
         Ok(self.0.ep())
     }
+
+    #[getter]
+    pub fn vertex(& mut self) -> PyResult<f64>{
+        Ok(self.0.vertex())
+    }
+
+    #[getter]
+    pub fn is_day(& mut self) -> PyResult<bool>{
+        Ok(self.0.is_day())
+    }
+
+    #[getter]
+    pub fn fortune(& mut self) -> PyResult<f64>{
+        Ok(self.0.fortune())
+    }
+
+    #[getter]
+    pub fn spirit(& mut self) -> PyResult<f64>{
+        Ok(self.0.spirit())
+    }
+
+    /// 十二宫宫头，system: 0:普拉西度， 1:科赫， 2:雷格蒙塔努斯， 3:坎帕努斯， 4:波菲利，
+    /// 5:等宫， 6:整宫， 7:阿卡比特斯， 8:莫里努斯；极区无解时改用波菲利制
+    #[pyo3(text_signature="($self, system)")]
+    pub fn cusps(& mut self, system: usize) -> PyResult<Vec<f64>>{
+        let system = reph::astronomy::HouseSystem::from(system);
+        Ok(self.0.cusps(system).cusps.to_vec())
+    }
 }
 
 
@@ -628,6 +718,25 @@ pub fn obliquity(jd: f64) -> PyResult<f64>{
     Ok(reph::astronomy::obliquity(jd))
 }
 
+/// 计算时差，单位为日
+#[pyfunction]
+pub fn equation_of_time(jd: f64, tz: f64) -> PyResult<f64>{
+    Ok(reph::astronomy::equation_of_time(jd, tz))
+}
+
+/// 计算真太阳时，返回儒略日
+#[pyfunction]
+pub fn true_solar_time(jd: f64, tz: f64, lon: f64) -> PyResult<f64>{
+    Ok(reph::astronomy::true_solar_time(jd, tz, lon))
+}
+
+/// 计算紫炁黄经，历元须由调用者给出
+#[pyfunction]
+#[pyo3(text_signature = "(jd, tz, epoch, epoch_lon)")]
+pub fn ziqi(jd: f64, tz: f64, epoch: f64, epoch_lon: f64) -> PyResult<f64>{
+    Ok(reph::astronomy::ziqi(jd, tz, epoch, epoch_lon))
+}
+
 /// Formats the sum of two numbers as string.
 #[pyfunction]
 fn sum_as_string(a: usize, b: usize) -> PyResult<String> {
@@ -650,6 +759,8 @@ fn ephemeris(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     lunnar.add_class::<GanZhi>()?;
     lunnar.add_function(wrap_pyfunction!(qi_accurate2, m)?)?;
     lunnar.add_function(wrap_pyfunction!(so_accurate2, m)?)?;
+    lunnar.add_function(wrap_pyfunction!(moon_phase, m)?)?;
+    lunnar.add_function(wrap_pyfunction!(lunation_number, m)?)?;
 
 
     // 天文模块
@@ -658,7 +769,10 @@ fn ephemeris(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     astronomy.add_class::<PlanetCoordinates>()?;
     astronomy.add_class::<Hourse>()?;
     astronomy.add_function(wrap_pyfunction!(obliquity, m)?)?;
+    astronomy.add_function(wrap_pyfunction!(equation_of_time, m)?)?;
+    astronomy.add_function(wrap_pyfunction!(true_solar_time, m)?)?;
     astronomy.add_function(wrap_pyfunction!(calculate_celestial_body,m)?)?;
+    astronomy.add_function(wrap_pyfunction!(ziqi, m)?)?;
 
     m.add_submodule(lunnar)?;
     m.add_submodule(astronomy)?;
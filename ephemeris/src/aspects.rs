@@ -0,0 +1,385 @@
+//! 相位计算
+//!
+//! 由盘中各点(星体、上升、中天等，见[`ChartPoint`])的黄经和黄经速度计算主要相位和次要相位，
+//! 容许度可以按相位和按点分别设置，入相、出相按各点实际的黄经速度判断
+
+use crate::internal::math_utils;
+use crate::astronomy::{ CelestialBody, ChartPoint, Hourse, PlanetCoordinates };
+
+/// 相位
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AspectKind {
+    /// 合相 0度
+    #[default]
+    Conjunction,
+    /// 冲相 180度
+    Opposition,
+    /// 三分相 120度
+    Trine,
+    /// 四分相 90度
+    Square,
+    /// 六分相 60度
+    Sextile,
+    /// 半六分相 30度
+    SemiSextile,
+    /// 半四分相 45度
+    SemiSquare,
+    /// 八分之三相 135度
+    Sesquiquadrate,
+    /// 梅花相 150度
+    Quincunx,
+    /// 五分相 72度
+    Quintile,
+    /// 倍五分相 144度
+    BiQuintile,
+}
+
+impl AspectKind {
+    /// 主要相位
+    pub const MAJOR: [AspectKind; 5] = [
+        AspectKind::Conjunction,
+        AspectKind::Opposition,
+        AspectKind::Trine,
+        AspectKind::Square,
+        AspectKind::Sextile,
+    ];
+
+    /// 次要相位
+    pub const MINOR: [AspectKind; 6] = [
+        AspectKind::SemiSextile,
+        AspectKind::SemiSquare,
+        AspectKind::Sesquiquadrate,
+        AspectKind::Quincunx,
+        AspectKind::Quintile,
+        AspectKind::BiQuintile,
+    ];
+
+    /// 相位角，弧度制
+    pub fn angle(&self) -> f64 {
+        let d: f64 = match self {
+            AspectKind::Conjunction => 0.0,
+            AspectKind::Opposition => 180.0,
+            AspectKind::Trine => 120.0,
+            AspectKind::Square => 90.0,
+            AspectKind::Sextile => 60.0,
+            AspectKind::SemiSextile => 30.0,
+            AspectKind::SemiSquare => 45.0,
+            AspectKind::Sesquiquadrate => 135.0,
+            AspectKind::Quincunx => 150.0,
+            AspectKind::Quintile => 72.0,
+            AspectKind::BiQuintile => 144.0,
+        };
+        d.to_radians()
+    }
+
+    /// 是否主要相位
+    pub fn is_major(&self) -> bool {
+        AspectKind::MAJOR.contains(self)
+    }
+}
+
+impl std::fmt::Display for AspectKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            AspectKind::Conjunction => "合相",
+            AspectKind::Opposition => "冲相",
+            AspectKind::Trine => "三分相",
+            AspectKind::Square => "四分相",
+            AspectKind::Sextile => "六分相",
+            AspectKind::SemiSextile => "半六分相",
+            AspectKind::SemiSquare => "半四分相",
+            AspectKind::Sesquiquadrate => "八分之三相",
+            AspectKind::Quincunx => "梅花相",
+            AspectKind::Quintile => "五分相",
+            AspectKind::BiQuintile => "倍五分相",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// 参与相位计算的点
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AspectPoint {
+    pub point: ChartPoint, // 点
+    pub lon: f64, // 黄经，弧度制
+    pub speed: f64, // 黄经速度，弧度/日
+}
+
+impl AspectPoint {
+    /// 由宫位盘计算某点的黄经及黄经速度，速度取前后1分钟的差分
+    ///
+    /// 上升、中天等角点的速度约为每日一周，星体的速度为视黄经速度
+    pub fn new(h: &Hourse, point: ChartPoint) -> Self {
+        let dh = 1.0 / 1440.0;
+        let at = |days: f64| h.shifted(days).point(point);
+        let lon = at(0.0);
+        let speed = math_utils::rad2rrad(at(dh) - at(-dh)) / (2.0 * dh);
+        AspectPoint { point, lon, speed }
+    }
+}
+
+impl From<&PlanetCoordinates> for AspectPoint {
+    fn from(p: &PlanetCoordinates) -> Self {
+        AspectPoint { point: ChartPoint::Body(p.body), lon: p.a_lon, speed: p.lon_speed }
+    }
+}
+
+/// 容许度设置
+///
+/// 两点间某个相位的容许度为 相位容许度 × (点1系数 + 点2系数) / 2，
+/// 只计算`aspects`中列出的相位，没有列出系数的点取1
+#[derive(Debug, Clone, PartialEq)]
+pub struct Orbs {
+    pub aspects: Vec<(AspectKind, f64)>, // 各相位容许度，弧度制
+    pub points: Vec<(ChartPoint, f64)>, // 各点的容许度系数
+}
+
+impl Default for Orbs {
+    /// 主要相位：合、冲、三分8度，四分7度，六分6度；次要相位2度；太阳、月亮系数1.25
+    fn default() -> Self {
+        let mut aspects: Vec<(AspectKind, f64)> = Orbs::major().aspects;
+        aspects.extend(AspectKind::MINOR.iter().map(|k| (*k, 2.0_f64.to_radians())));
+        Orbs { aspects, ..Orbs::major() }
+    }
+}
+
+impl Orbs {
+    /// 只计算主要相位
+    pub fn major() -> Self {
+        let aspects = [8.0_f64, 8.0, 8.0, 7.0, 6.0]
+            .iter()
+            .zip(AspectKind::MAJOR.iter())
+            .map(|(o, k)| (*k, o.to_radians()))
+            .collect();
+        let points = vec![
+            (ChartPoint::Body(CelestialBody::Sun), 1.25),
+            (ChartPoint::Body(CelestialBody::Moon), 1.25)
+        ];
+        Orbs { aspects, points }
+    }
+
+    /// 设置相位容许度(弧度制)，未列出的相位会被加入
+    pub fn set_aspect(&mut self, kind: AspectKind, orb: f64) -> &mut Self {
+        match self.aspects.iter_mut().find(|x| x.0 == kind) {
+            Some(x) => x.1 = orb,
+            None => self.aspects.push((kind, orb)),
+        }
+        self
+    }
+
+    /// 设置某点的容许度系数
+    pub fn set_point(&mut self, point: ChartPoint, factor: f64) -> &mut Self {
+        match self.points.iter_mut().find(|x| x.0 == point) {
+            Some(x) => x.1 = factor,
+            None => self.points.push((point, factor)),
+        }
+        self
+    }
+
+    fn factor(&self, point: ChartPoint) -> f64 {
+        self.points
+            .iter()
+            .find(|x| x.0 == point)
+            .map(|x| x.1)
+            .unwrap_or(1.0)
+    }
+
+    /// 两点间某个相位的容许度
+    pub fn orb(&self, kind: AspectKind, p1: ChartPoint, p2: ChartPoint) -> Option<f64> {
+        let orb = self.aspects.iter().find(|x| x.0 == kind)?.1;
+        Some((orb * (self.factor(p1) + self.factor(p2))) / 2.0)
+    }
+}
+
+/// 相位结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aspect {
+    pub p1: ChartPoint,
+    pub p2: ChartPoint,
+    pub kind: AspectKind,
+    pub orb: f64, // 与精确相位的偏差，弧度制，正值表示两点角距大于相位角
+    pub applying: bool, // 入相(趋近精确相位)为真，出相为假
+}
+
+impl std::fmt::Display for Aspect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} {} {:?} 容许度 {:.2}° {}",
+            self.p1,
+            self.kind,
+            self.p2,
+            self.orb.to_degrees(),
+            if self.applying { "入相" } else { "出相" }
+        )
+    }
+}
+
+/// 计算各点之间的相位
+///
+/// 两两比较各点，角距落在相位容许度之内即为成相位，按输入顺序返回。
+/// 角距同时落在几个相位的容许度内时，每对点只取偏差最小的相位。
+/// 角距的变化率由两点黄经速度得到，角距趋近相位角为入相，远离为出相
+///
+/// # Example
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::aspects::*;
+/// use rust_ephemeris::JulianDate;
+///
+/// let jd = JulianDate::from_day(2023, 3, 21.0 + 18.5 / 24.0).jd;
+/// let h = Hourse::from_local(jd, -8.0, 2.12, 0.54);
+/// let mut points: Vec<AspectPoint> = [CelestialBody::Sun, CelestialBody::Moon, CelestialBody::Mars]
+///     .iter()
+///     .map(|b| AspectPoint::new(&h, ChartPoint::Body(*b)))
+///     .collect();
+/// points.push(AspectPoint::new(&h, ChartPoint::Asc));
+/// for a in aspects(&points, &Orbs::default()) {
+///     println!("{}", a);
+/// }
+///
+/// // 新月前后日月合相，月亮快，新月前入相
+/// let sun = AspectPoint::new(&h, ChartPoint::Body(CelestialBody::Sun));
+/// let moon = AspectPoint::new(&h, ChartPoint::Body(CelestialBody::Moon));
+/// let a = aspects(&[sun, moon], &Orbs::major());
+/// assert_eq!(a[0].kind, AspectKind::Conjunction);
+/// assert!(a[0].applying);
+/// ```
+pub fn aspects(points: &[AspectPoint], orbs: &Orbs) -> Vec<Aspect> {
+    let mut r = Vec::new();
+    for (i, a) in points.iter().enumerate() {
+        for b in points[i + 1..].iter() {
+            let d = math_utils::rad2rrad(b.lon - a.lon);
+            // 角距(0-180度)的变化率
+            let rate = d.signum() * (b.speed - a.speed);
+            // 容许度重叠时只取偏差最小的相位
+            let mut best: Option<Aspect> = None;
+            for (kind, _) in orbs.aspects.iter() {
+                let orb = match orbs.orb(*kind, a.point, b.point) {
+                    Some(x) => x,
+                    None => continue,
+                };
+                let delta = d.abs() - kind.angle();
+                if delta.abs() > orb || best.is_some_and(|x| x.orb.abs() <= delta.abs()) {
+                    continue;
+                }
+                // 偏差与角距变化率异号即趋近精确相位
+                let applying = delta * rate < 0.0;
+                best = Some(Aspect { p1: a.point, p2: b.point, kind: *kind, orb: delta, applying });
+            }
+            r.extend(best);
+        }
+    }
+    r
+}
+
+#[cfg(test)]
+fn point(body: CelestialBody, lon: f64, speed: f64) -> AspectPoint {
+    AspectPoint { point: ChartPoint::Body(body), lon: lon.to_radians(), speed: speed.to_radians() }
+}
+
+#[test]
+fn test_applying() {
+    let orbs = Orbs::major();
+    let sun = |lon: f64| point(CelestialBody::Sun, lon, 1.0);
+    let mars = |lon: f64, speed: f64| point(CelestialBody::Mars, lon, speed);
+
+    // 快星在后追赶慢星为入相，越过后为出相
+    let a = aspects(&[sun(100.0), mars(95.0, 0.5)], &orbs);
+    assert_eq!(a.len(), 1);
+    assert_eq!(a[0].kind, AspectKind::Conjunction);
+    assert!(!a[0].applying);
+    assert!(aspects(&[sun(95.0), mars(100.0, 0.5)], &orbs)[0].applying);
+    // 交换两点顺序结果不变
+    assert!(aspects(&[mars(100.0, 0.5), sun(95.0)], &orbs)[0].applying);
+
+    // 逆行：火星后退迎向太阳
+    assert!(aspects(&[sun(100.0), mars(105.0, -0.3)], &orbs)[0].applying);
+    assert!(!aspects(&[sun(100.0), mars(95.0, -0.3)], &orbs)[0].applying);
+
+    // 四分相两侧：太阳较快，与前方火星的角距缩小，与后方火星的角距增大
+    let a = aspects(&[sun(10.0), mars(103.0, 0.5)], &orbs);
+    assert_eq!(a[0].kind, AspectKind::Square);
+    assert!((a[0].orb.to_degrees() - 3.0).abs() < 1e-9);
+    assert!(a[0].applying);
+    assert!(!aspects(&[sun(10.0), mars(97.0, 0.5)], &orbs)[0].applying);
+    assert!(aspects(&[sun(10.0), mars(-77.0, 0.5)], &orbs)[0].applying);
+    assert!(!aspects(&[sun(10.0), mars(-83.0, 0.5)], &orbs)[0].applying);
+}
+
+#[test]
+fn test_opposition_wrap() {
+    let orbs = Orbs::major();
+    // 角距在±180度两侧，结果应连续
+    for (lon, speed, applying) in [
+        (178.0, 0.5, false),
+        (182.0, 0.5, true),
+        (178.0, 2.0, true),
+        (182.0, 2.0, false),
+        (-178.0, -0.5, true),
+        (-182.0, -0.5, false),
+    ] {
+        let a = aspects(
+            &[point(CelestialBody::Sun, 0.0, 1.0), point(CelestialBody::Mars, lon, speed)],
+            &orbs
+        );
+        assert_eq!(a.len(), 1, "lon {}", lon);
+        assert_eq!(a[0].kind, AspectKind::Opposition);
+        assert!((a[0].orb.abs().to_degrees() - 2.0).abs() < 1e-9);
+        assert_eq!(a[0].applying, applying, "lon {}", lon);
+    }
+}
+
+#[test]
+fn test_orb_factor() {
+    let mut orbs = Orbs::major();
+    let sun = ChartPoint::Body(CelestialBody::Sun);
+    let moon = ChartPoint::Body(CelestialBody::Moon);
+    let mars = ChartPoint::Body(CelestialBody::Mars);
+    let deg = |x: Option<f64>| x.unwrap().to_degrees();
+    assert!((deg(orbs.orb(AspectKind::Conjunction, sun, moon)) - 10.0).abs() < 1e-9);
+    assert!((deg(orbs.orb(AspectKind::Conjunction, sun, mars)) - 9.0).abs() < 1e-9);
+    assert!((deg(orbs.orb(AspectKind::Square, mars, ChartPoint::Asc)) - 7.0).abs() < 1e-9);
+    assert!(orbs.orb(AspectKind::Quincunx, sun, mars).is_none());
+
+    orbs.set_point(mars, 0.5).set_aspect(AspectKind::Quincunx, 3.0_f64.to_radians());
+    assert!((deg(orbs.orb(AspectKind::Conjunction, sun, mars)) - 7.0).abs() < 1e-9);
+    assert!((deg(orbs.orb(AspectKind::Quincunx, mars, mars)) - 1.5).abs() < 1e-9);
+
+    // 8.5度：太阳与火星成合相，火星与木星则不成
+    let p = [
+        point(CelestialBody::Sun, 0.0, 1.0),
+        point(CelestialBody::Mars, 8.5, 0.5),
+        point(CelestialBody::Jupiter, 17.0, 0.1),
+    ];
+    let a = aspects(&p, &Orbs::major());
+    assert_eq!(a.len(), 1);
+    assert_eq!(a[0].p2, mars);
+}
+
+#[test]
+fn test_overlapping_orbs() {
+    // 四分相和八分之三相容许度都放宽到30度，角距同时落在多个相位的容许度内时只取偏差最小的一个
+    let mut orbs = Orbs::major();
+    orbs.set_aspect(AspectKind::Square, 30.0_f64.to_radians());
+    orbs.set_aspect(AspectKind::Sesquiquadrate, 30.0_f64.to_radians());
+    let pair = |lon: f64| {
+        aspects(
+            &[point(CelestialBody::Mercury, 0.0, 1.0), point(CelestialBody::Mars, lon, 0.5)],
+            &orbs
+        )
+    };
+    // 四分相偏差20度，八分之三相偏差25度
+    let a = pair(110.0);
+    assert_eq!(a.len(), 1);
+    assert_eq!(a[0].kind, AspectKind::Square);
+    assert!((a[0].orb.to_degrees() - 20.0).abs() < 1e-9);
+    // 三分相偏差7度，八分之三相偏差8度
+    let a = pair(127.0);
+    assert_eq!(a.len(), 1);
+    assert_eq!(a[0].kind, AspectKind::Trine);
+    // 八分之三相偏差5度，三分相超出容许度
+    let a = pair(-130.0);
+    assert_eq!(a.len(), 1);
+    assert_eq!(a[0].kind, AspectKind::Sesquiquadrate);
+}
@@ -2,6 +2,14 @@ use std::f64::consts::PI;
 use crate::internal::math_utils;
 use crate::internal::constants;
 use crate::internal::ephemeris::{compute_position, self};
+use crate::internal::rise_set;
+use crate::internal::phenomena;
+use crate::internal::houses;
+use crate::internal::xiu;
+use crate::internal::siyu;
+use crate::internal::physical;
+use crate::internal::lunnar::JulianDate;
+use crate::time::Epoch;
 
 /// 黄赤角计算
 /// 
@@ -17,9 +25,176 @@ pub fn obliquity(jd:f64)->f64{
 }
 
 
+/// 计算精度
+///
+/// 选择行星、月亮位置级数的截断项数，项数越少计算越快。各级别在1900-2100年间与全部项相比的
+/// 最大误差(视黄经、视黄纬，行星含太阳)：
+///
+/// | 级别 | 行星 | 月亮 |
+/// | --- | --- | --- |
+/// | `Full` | 0 | 0 |
+/// | `High` | 1角秒 | 2.5角秒 |
+/// | `Medium` | 15角秒 | 9角秒 |
+/// | `Low` | 55角秒 | 30角秒 |
+///
+/// 冥王星始终使用全部项。通过[`Options`]选择
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Precision {
+    /// 全部项
+    #[default]
+    Full,
+    /// 高精度，误差在角秒级
+    High,
+    /// 中等精度，误差十几角秒
+    Medium,
+    /// 低精度，误差小于1角分
+    Low,
+}
+
+impl From<usize> for Precision {
+    fn from(value: usize) -> Self {
+        match value {
+            1 => Precision::High,
+            2 => Precision::Medium,
+            3 => Precision::Low,
+            _ => Precision::Full,
+        }
+    }
+}
+
+/// 计算选项
+///
+/// 通过[`Options::run`]作用于一段计算，只在闭包执行期间、只对当前线程有效，
+/// 不影响其他线程或闭包之外的计算。闭包内的所有计算(农历、节气、日月食、出没、位置，
+/// 以及[`Instant`](crate::time::Instant)的时间尺度换算)都按选项进行
+///
+/// # Example
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd = JulianDate::from_day(2023, 7, 23.5).jd;
+/// let full = calculate_celestial_body(CelestialBody::Saturn, jd, -8.0, 2.03, 0.7);
+/// let opts = Options { precision: Precision::Low, ..Default::default() };
+/// let low = opts.run(|| calculate_celestial_body(CelestialBody::Saturn, jd, -8.0, 2.03, 0.7));
+/// assert!((low.a_lon - full.a_lon).abs().to_degrees() < 1.0 / 60.0);
+/// assert!(low.a_lon != full.a_lon);
+///
+/// // 闭包之外仍为全部项
+/// let again = calculate_celestial_body(CelestialBody::Saturn, jd, -8.0, 2.03, 0.7);
+/// assert_eq!(again.a_lon, full.a_lon);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Options {
+    pub precision: Precision, // 级数截断精度
+    pub delta_t: DeltaTModel, // ΔT模型
+}
+
+impl Options {
+    /// 按选项执行`f`
+    pub fn run<R>(&self, f: impl FnOnce() -> R) -> R {
+        let (model, table) = match &self.delta_t {
+            DeltaTModel::Default => (0, Vec::new()),
+            DeltaTModel::MorrisonStephenson2004 => (1, Vec::new()),
+            DeltaTModel::EspenakMeeus2006 => (2, Vec::new()),
+            DeltaTModel::Table(t) => (3, t.clone()),
+        };
+        ephemeris::with_precision(self.precision as usize, || math_utils::with_dt_model(model, table, f))
+    }
+}
+
+/// ΔT(TT-UT)模型
+///
+/// 力学时与世界时之差决定了节气、朔望、天象等事件的世界时时刻，以及给定世界时的星体位置。
+/// 通过[`Options`]选择
+///
+/// # Example
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::lunnar::*;
+/// assert!((delta_t(2000.0) - 63.9).abs() < 0.5);
+/// let em = Options { delta_t: DeltaTModel::EspenakMeeus2006, ..Default::default() };
+/// assert!((em.run(|| delta_t(2000.0)) - 63.86).abs() < 1e-9);
+/// let ms = Options { delta_t: DeltaTModel::MorrisonStephenson2004, ..Default::default() };
+/// assert!((ms.run(|| delta_t(0.0)) - 10580.0).abs() < 1e-6);
+///
+/// // ΔT取0时，朔的北京时间推后ΔT
+/// let dt = delta_t(2023.2);
+/// let k = lunation_number(2460025.5);
+/// let before = moon_phase(k, MoonPhase::NewMoon);
+/// let zero = Options { delta_t: DeltaTModel::Table(vec![(1900.0, 0.0), (2100.0, 0.0)]), ..Default::default() };
+/// let after = zero.run(|| moon_phase(k, MoonPhase::NewMoon));
+/// assert!(((after - before) * 86400.0 - dt).abs() < 0.1);
+/// assert_eq!(moon_phase(k, MoonPhase::NewMoon), before);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum DeltaTModel {
+    /// 内置表(寿星万年历)，表后按加速度31秒/世纪²外推
+    #[default]
+    Default,
+    /// Morrison & Stephenson(2004)，-500年至1600年按其表1插值，-500年以前取长期抛物线-20+32u²，
+    /// 1600年以后取内置实测值
+    MorrisonStephenson2004,
+    /// Espenak & Meeus(2006)多项式，NASA日月食表使用
+    EspenakMeeus2006,
+    /// 用户表，(年, ΔT秒)，表内线性插值，表外二次外推，少于2项时按内置表
+    Table(Vec<(f64, f64)>),
+}
+
+impl DeltaTModel {
+    /// 从文本读取用户ΔT表
+    ///
+    /// 每行为年份和ΔT(秒)，以空白或逗号分隔，`#`开头的行和空行忽略，多余的列忽略。
+    /// 格式错误或少于2行数据时返回`None`
+    ///
+    /// # Example
+    /// ```
+    /// use rust_ephemeris::astronomy::*;
+    /// let m = DeltaTModel::parse_table("# year dt\n2000 63.83\n2010, 66.07\n2020 69.36\n").unwrap();
+    /// assert_eq!(m, DeltaTModel::Table(vec![(2000.0, 63.83), (2010.0, 66.07), (2020.0, 69.36)]));
+    /// assert!(DeltaTModel::parse_table("2000 abc").is_none());
+    /// ```
+    pub fn parse_table(text: &str) -> Option<DeltaTModel> {
+        let mut table = Vec::new();
+        for line in text.lines().map(|x| x.trim()) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut it = line.split(|c: char| c.is_whitespace() || c == ',').filter(|x| !x.is_empty());
+            let y = it.next()?.parse::<f64>().ok()?;
+            let dt = it.next()?.parse::<f64>().ok()?;
+            table.push((y, dt));
+        }
+        if table.len() < 2 { None } else { Some(DeltaTModel::Table(table)) }
+    }
+}
+
+/// 按当前[`Options`]的模型计算ΔT(TT-UT)，单位秒
+///
+/// # Argument
+/// - `year`: 年份，带小数，如2000.5
+pub fn delta_t(year: f64) -> f64 {
+    math_utils::delta_t(year)
+}
+
 /// 星体
 ///
 /// 0=>地球， 1=>火星...10=>月球
+///
+/// 11-16为七政四余中的四余：罗睺、计都为月亮升、降交点，月孛为月亮远地点，分平、真两种，
+/// 真值取月亮瞬时轨道(密切轨道)。四余没有视差，罗睺、计都的距离为0。
+/// 紫炁没有对应天体，历元须由调用者给出，见[`ziqi`]
+///
+/// # Example
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd = JulianDate::from_day(2023, 7, 23.5).jd;
+/// let rahu = calculate_celestial_body(CelestialBody::MeanRahu, jd, -8.0, 2.03, 0.7);
+/// let ketu = calculate_celestial_body(CelestialBody::MeanKetu, jd, -8.0, 2.03, 0.7);
+/// println!("罗睺 {}", rahu);
+/// assert!(rahu.retrograde);
+/// assert!(((ketu.a_lon - rahu.a_lon).abs() - std::f64::consts::PI).abs() < 1e-9);
+/// ```
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub enum CelestialBody {
     Earth, // 地球
@@ -34,6 +209,12 @@ pub enum CelestialBody {
     Sun, // 太阳
     #[default]
     Moon, // 月
+    MeanRahu, // 平罗睺，月亮平升交点
+    TrueRahu, // 真罗睺，月亮真升交点
+    MeanKetu, // 平计都，月亮平降交点
+    TrueKetu, // 真计都，月亮真降交点
+    MeanYuebei, // 平月孛，月亮平远地点
+    TrueYuebei, // 真月孛，月亮瞬时轨道远地点
 }
 
 impl From<usize> for CelestialBody {
@@ -50,12 +231,88 @@ impl From<usize> for CelestialBody {
             8 => Self::Pluto,
             9 => Self::Sun,
             10 => Self::Moon,
+            11 => Self::MeanRahu,
+            12 => Self::TrueRahu,
+            13 => Self::MeanKetu,
+            14 => Self::TrueKetu,
+            15 => Self::MeanYuebei,
+            16 => Self::TrueYuebei,
             _ => panic!("invalid value for CelestialBody "),
         };
         resulut
     }
 }
 
+/// 观测者
+///
+/// 观测地经纬度、海拔以及气压、气温，用于站心坐标(视差)、大气折射以及地平俯角计算。
+/// 通过[`Observer::new`]构造时气压按标准大气由海拔估算(海平面1010百帕)，气温取10摄氏度，
+/// 可以直接修改对应字段
+///
+/// # Example
+/// 海拔3000米的观测站，日出比海平面早
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd = JulianDate::from_day(2023, 7, 23.0).jd;
+/// let mut obs = Observer::new(100.0_f64.to_radians(), 30.0_f64.to_radians(), 3000.0);
+/// obs.temperature = 0.0;
+/// println!("气压 {:.1}百帕 地平俯角 {:.2}°", obs.pressure, obs.dip().to_degrees());
+/// let high = rise_transit_set_at(CelestialBody::Sun, jd, -8.0, &obs);
+/// let low = rise_transit_set(CelestialBody::Sun, jd, -8.0, obs.lon, obs.lat);
+/// assert!(high.rise.unwrap() < low.rise.unwrap());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Observer {
+    pub lon: f64, // 经度，东经为正，弧度制
+    pub lat: f64, // 纬度，弧度制
+    pub elevation: f64, // 海拔，米
+    pub pressure: f64, // 气压，百帕
+    pub temperature: f64, // 气温，摄氏度
+}
+
+impl Default for Observer {
+    fn default() -> Self {
+        Observer::new(0.0, 0.0, 0.0)
+    }
+}
+
+impl Observer {
+    /// 由经纬度(弧度制)和海拔(米)构造观测者，气压、气温取标准大气
+    pub fn new(lon: f64, lat: f64, elevation: f64) -> Self {
+        Observer {
+            lon,
+            lat,
+            elevation,
+            pressure: 1010.0 * (1.0 - 2.25577e-5 * elevation).powf(5.25588),
+            temperature: 10.0,
+        }
+    }
+
+    /// 大气折射相对标准大气(1010百帕、10摄氏度)的比例
+    pub fn refraction_factor(&self) -> f64 {
+        (self.pressure / 1010.0) * (283.0 / (273.0 + self.temperature))
+    }
+
+    /// 地平俯角，弧度制
+    ///
+    /// 高处观测时视地平低于天文地平的角度，按航海常用的1.76'√h计算(含地面折射)
+    pub fn dip(&self) -> f64 {
+        if self.elevation <= 0.0 {
+            return 0.0;
+        }
+        (1.76 / 60.0 * self.elevation.sqrt()).to_radians()
+    }
+
+    fn site(&self) -> ephemeris::Site {
+        ephemeris::Site {
+            high: self.elevation / 1000.0,
+            refr: self.refraction_factor(),
+            dip: self.dip(),
+        }
+    }
+}
+
 /// 行星位置表示
 ///
 /// 主要实现format函数，以及获取当前黄经信息等便捷函数
@@ -80,6 +337,11 @@ pub struct PlanetCoordinates {
     pub alt: f64, // 高度角
     pub sid_time: (f64, f64), // 恒星时, 真、平
     pub body: CelestialBody, //星体
+
+    pub lon_speed: f64, // 视黄经速度，弧度/日
+    pub lat_speed: f64, // 视黄纬速度，弧度/日
+    pub dist_speed: f64, // 地心距变化，单位同地心距
+    pub retrograde: bool, // 是否逆行
 }
 
 impl std::fmt::Display for PlanetCoordinates {
@@ -87,7 +349,8 @@ impl std::fmt::Display for PlanetCoordinates {
         let mut s = String::new();
 
         let body = self.body;
-        if body != CelestialBody::Moon {
+        // 四余为虚点，没有日心坐标
+        if (body as usize) < CelestialBody::MeanRahu as usize {
             s += &format!(
                 "黄经一 {} 黄纬一 {} 向径一 {:.2}\n",
                 math_utils::Angle::from_f64(self.eclon).degress(2), //rad2str(z[0], 0),
@@ -127,18 +390,25 @@ impl std::fmt::Display for PlanetCoordinates {
             math_utils::Angle::from_f64(gst_ping).time(2), //,rad2str(rad2mrad(gst_ping), 1),
             math_utils::Angle::from_f64(gst).time(2) //(rad2mrad(gst), 1)
         );
+        s += &format!(
+            "黄经速度 {:.4}°/日 黄纬速度 {:.4}°/日 距离变化 {:.6}/日{}\n",
+            self.lon_speed.to_degrees(),
+            self.lat_speed.to_degrees(),
+            self.dist_speed,
+            if self.retrograde { " 逆行" } else { "" }
+        );
         write!(f, "{}", s)
     }
 }
 
 /// 计算天体位置信息
 ///
-/// 主要包含黄经、黄纬度，视黄经、黄纬以及方位角信息
+/// 主要包含黄经、黄纬度，视黄经、黄纬以及方位角信息，以及视黄经、视黄纬、地心距的日变化和逆行标志
 ///
 /// # Arguments
 ///
 /// * `body` - 需要计算的天体
-/// * `jd` - 儒略日
+/// * `jd` - 儒略日（本地时间）或[`Instant`](crate::time::Instant)
 /// * `tz` - 时区， 比如东八区`tz=-8.0`
 /// * `lon` - 经度信息， 注意是弧度制
 /// * `lat` - 纬度信息，也是采用弧度制
@@ -166,19 +436,33 @@ impl std::fmt::Display for PlanetCoordinates {
 ///    lat
 /// );
 /// println!("{}", pos);
+/// assert!(!pos.retrograde && pos.lon_speed > 0.0);
 /// ```
 pub fn calculate_celestial_body(
     body: CelestialBody,
-    jd: f64,
+    jd: impl Epoch,
     tz: f64,
     lon: f64,
     lat: f64
 ) -> PlanetCoordinates {
-    let mut jd = jd - constants::J2000;
+    calculate_celestial_body_at(body, jd, tz, &Observer::new(lon, lat, 0.0))
+}
 
-    jd = jd + tz / 24.0 + math_utils::dt_t(jd); // 转为标准力学时
-    println!("xt:{} , jd:{}, l:{}, fa:{}", body as usize, jd, lon, lat);
+/// 按观测者计算天体位置信息
+///
+/// 同[`calculate_celestial_body`]，站赤经、站赤纬考虑观测者海拔，高度角按观测者的气压、气温修正大气折射
+pub fn calculate_celestial_body_at(
+    body: CelestialBody,
+    jd: impl Epoch,
+    tz: f64,
+    observer: &Observer
+) -> PlanetCoordinates {
+    body_at_tt(body, jd.tt(tz), observer) // 转为标准力学时
+}
 
+// 力学时(J2000起算的日数)的天体位置信息
+fn body_at_tt(body: CelestialBody, jd: f64, observer: &Observer) -> PlanetCoordinates {
+    let (lon, lat) = (observer.lon, observer.lat);
     let (
         eclon_,
         eclat_,
@@ -195,7 +479,8 @@ pub fn calculate_celestial_body(
         az_,
         alt_,
         sid_time_,
-    ) = compute_position(body as usize, jd, lon, lat);
+    ) = ephemeris::compute_position_at(body as usize, jd, lon, lat, &observer.site());
+    let (lon_speed, lat_speed, dist_speed) = ephemeris::compute_speed(body as usize, jd);
 
     let pos = PlanetCoordinates {
         body: body,
@@ -214,10 +499,1284 @@ pub fn calculate_celestial_body(
         az: az_,
         alt: alt_,
         sid_time: sid_time_,
+        lon_speed,
+        lat_speed,
+        dist_speed,
+        retrograde: lon_speed < 0.0,
     };
     pos
 }
 
+/// 计算紫炁黄经
+///
+/// 紫炁为虚星，没有对应的天体，按28年一周天匀速顺行。各流派起算点不同，
+/// 须由调用者给出某一时刻的紫炁黄经作为历元。返回瞬时平黄道黄经，弧度制
+///
+/// # Arguments
+///
+/// * `jd` - 儒略日（本地时间）或[`Instant`](crate::time::Instant)
+/// * `tz` - 时区， 比如东八区`tz=-8.0`
+/// * `epoch` - 历元儒略日（本地时间，同一时区）
+/// * `epoch_lon` - 历元时紫炁黄经，弧度制
+///
+/// # Example
+/// 与春分点计算相位
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::aspects::*;
+/// use rust_ephemeris::JulianDate;
+/// let epoch = JulianDate::from_day(2000, 1, 1.5).jd;
+/// let jd = epoch + 7.0 * 365.25;
+/// let lon = ziqi(jd, -8.0, epoch, 0.0);
+/// // 7年行一象限
+/// assert!((lon.to_degrees() - 90.0).abs() < 1e-4);
+/// let speed = (360.0_f64 / (28.0 * 365.25)).to_radians();
+/// let p = AspectPoint { point: ChartPoint::Lon(lon), lon, speed };
+/// let q = AspectPoint { point: ChartPoint::Lon(0.0), lon: 0.0, speed: 0.0 };
+/// let r = aspects(&[p, q], &Orbs::major());
+/// assert_eq!(r.len(), 1);
+/// assert_eq!(r[0].kind, AspectKind::Square);
+/// assert!(r[0].orb.to_degrees().abs() < 1e-4);
+/// ```
+pub fn ziqi(jd: impl Epoch, tz: f64, epoch: impl Epoch, epoch_lon: f64) -> f64 {
+    siyu::ziqi(jd.tt(tz) / 36525.0, epoch.tt(tz) / 36525.0, epoch_lon)
+}
+
+// 本地儒略日转为力学时（J2000起算的日数）
+pub(crate) fn to_tt(jd: f64, tz: f64) -> f64 {
+    let t = jd - constants::J2000 + tz / 24.0;
+    t + math_utils::dt_t(t)
+}
+
+// 力学时（J2000起算的日数）转为本地儒略日
+pub(crate) fn from_tt(t: f64, tz: f64) -> f64 {
+    t - math_utils::dt_t(t) - tz / 24.0 + constants::J2000
+}
+
+// 儒略日格式化为 年-月-日 时分秒
+pub(crate) fn jd2str(jd: f64) -> String {
+    let (y, m, d) = JulianDate::jd2day(jd);
+    let d1 = d.floor();
+    let mut t = math_utils::Angle::from_f64((d - d1) * 2.0 * PI);
+    format!("{}-{}-{} {}", y, m, d1 as i32, t.time(0))
+}
+
+/// 计算时差
+///
+/// 真太阳时减平太阳时，单位为日，正值表示真太阳时快于平太阳时
+///
+/// # Arguments
+///
+/// * `jd` - 儒略日（本地时间）或[`Instant`](crate::time::Instant)
+/// * `tz` - 时区， 比如东八区`tz=-8.0`
+///
+/// # Example
+/// 11月初时差最大，约16分钟
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd = JulianDate::from_day(2023, 11, 3.5).jd;
+/// let e = equation_of_time(jd, -8.0) * 1440.0;
+/// println!("时差 {:.2}分钟", e); // 时差 16.46分钟
+/// assert!((e - 16.4).abs() < 0.1);
+/// ```
+pub fn equation_of_time(jd: impl Epoch, tz: f64) -> f64 {
+    ephemeris::equation_of_time(jd.tt(tz)) / (2.0 * PI)
+}
+
+/// 计算地方平太阳时
+///
+/// 返回地方平太阳时对应的儒略日，即按观测地经度修正后的钟表时间
+///
+/// # Arguments
+///
+/// * `jd` - 儒略日（本地时间）或[`Instant`](crate::time::Instant)
+/// * `tz` - 时区， 比如东八区`tz=-8.0`
+/// * `lon` - 经度信息， 东经为正，弧度制
+pub fn mean_solar_time(jd: impl Epoch, tz: f64, lon: f64) -> f64 {
+    jd.local(tz) + tz / 24.0 + lon / (2.0 * PI)
+}
+
+/// 计算真太阳时
+///
+/// 返回地方真太阳时对应的儒略日，平太阳时加上时差，太阳上中天时为正午12点
+///
+/// # Arguments
+///
+/// * `jd` - 儒略日（本地时间）或[`Instant`](crate::time::Instant)
+/// * `tz` - 时区， 比如东八区`tz=-8.0`
+/// * `lon` - 经度信息， 东经为正，弧度制
+///
+/// # Example
+/// 2023-11-11 北京时间12:00，乌鲁木齐的真太阳时
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd = JulianDate::from_day(2023, 11, 11.5).jd;
+/// let t = true_solar_time(jd, -8.0, 87.6_f64.to_radians());
+/// let (y, m, d) = JulianDate::jd2day(t);
+/// println!("{}-{}-{} {:.2}时", y, m, d.floor(), d.fract() * 24.0); // 2023-11-11 10.11时
+/// ```
+pub fn true_solar_time(jd: impl Epoch, tz: f64, lon: f64) -> f64 {
+    mean_solar_time(jd, tz, lon) + equation_of_time(jd, tz)
+}
+
+/// 天体全天相对地平的状态
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum HorizonState {
+    /// 当天有升或者落
+    #[default]
+    Normal,
+    /// 全天在地平之上（拱极、极昼）
+    Circumpolar,
+    /// 全天在地平之下（极夜）
+    NeverRises,
+}
+
+/// 天体升、中天、落
+///
+/// 时间都是本地时间的儒略日，当天没有对应事件时为`None`，
+/// 比如月亮大约每月有一天不升或者不落
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RiseTransitSet {
+    pub body: CelestialBody, // 星体
+    pub rise: Option<f64>, // 升
+    pub transit: Option<f64>, // 上中天
+    pub set: Option<f64>, // 落
+    pub state: HorizonState, // 全天状态
+}
+
+impl std::fmt::Display for RiseTransitSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fmt = |x: Option<f64>| match x {
+            Some(jd) => jd2str(jd),
+            None => "--".to_string(),
+        };
+        let state = match self.state {
+            HorizonState::Normal => "",
+            HorizonState::Circumpolar => "(全天在地平之上)",
+            HorizonState::NeverRises => "(全天在地平之下)",
+        };
+        write!(
+            f,
+            "升 {} 中天 {} 落 {}{}",
+            fmt(self.rise),
+            fmt(self.transit),
+            fmt(self.set),
+            state
+        )
+    }
+}
+
+/// 计算天体升、中天、落
+///
+/// 计算本地日期当天(0时到24时)天体的升起、上中天和落下时刻，采用站心坐标并考虑大气折射,
+/// 以天体上边缘与地平相切为出没，太阳、月亮扣除视半径，月亮站心坐标已含视差。
+/// 当天没有升落时通过`state`区分拱极和不升
+///
+/// # Arguments
+///
+/// * `body` - 需要计算的天体，不支持地球
+/// * `jd` - 当天任一时刻的儒略日（本地时间）或[`Instant`](crate::time::Instant)
+/// * `tz` - 时区， 比如东八区`tz=-8.0`
+/// * `lon` - 经度信息， 东经为正，弧度制
+/// * `lat` - 纬度信息，弧度制
+///
+/// # Example
+/// 计算2023-7-23 北京日出日落
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd = JulianDate::from_day(2023, 7, 23.0).jd;
+/// let lon = 116.383_f64.to_radians();
+/// let lat = 39.9_f64.to_radians();
+/// let r = rise_transit_set(CelestialBody::Sun, jd, -8.0, lon, lat);
+/// println!("{}", r); // 升 2023-7-23 5h 4m 28.0s 中天 2023-7-23 12h 20m 59.0s 落 2023-7-23 19h 37m 1.0s
+/// assert_eq!(r.state, HorizonState::Normal);
+/// ```
+pub fn rise_transit_set(
+    body: CelestialBody,
+    jd: impl Epoch,
+    tz: f64,
+    lon: f64,
+    lat: f64
+) -> RiseTransitSet {
+    rise_transit_set_at(body, jd, tz, &Observer::new(lon, lat, 0.0))
+}
+
+/// 按观测者计算天体升、中天、落
+///
+/// 同[`rise_transit_set`]，考虑观测者海拔的视差和地平俯角，大气折射按观测者的气压、气温修正
+pub fn rise_transit_set_at(body: CelestialBody, jd: impl Epoch, tz: f64, observer: &Observer) -> RiseTransitSet {
+    assert!(body != CelestialBody::Earth, "can not rise or set the earth");
+    let t0 = to_tt((jd.local(tz) + 0.5).floor() - 0.5, tz);
+    let (rise, transit, set, above) = rise_set::rise_transit_set(
+        body as usize,
+        t0,
+        observer.lon,
+        observer.lat,
+        &observer.site()
+    );
+    let state = match (rise, set, above) {
+        (None, None, true) => HorizonState::Circumpolar,
+        (None, None, false) => HorizonState::NeverRises,
+        _ => HorizonState::Normal,
+    };
+    RiseTransitSet {
+        body,
+        rise: rise.map(|t| from_tt(t, tz)),
+        transit: transit.map(|t| from_tt(t, tz)),
+        set: set.map(|t| from_tt(t, tz)),
+        state,
+    }
+}
+
+/// 太阳高度事件
+///
+/// 太阳中心几何高度(不含大气折射)穿越`alt`的时刻，时间是本地时间的儒略日。
+/// 极区当天不穿越时`state`为[`Circumpolar`](HorizonState::Circumpolar)表示全天高于`alt`
+/// (比如白夜时天文昏影终不会到来)，[`NeverRises`](HorizonState::NeverRises)表示全天低于`alt`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SolarAltitudeEvent {
+    pub alt: f64, // 太阳高度，弧度制
+    pub morning: Option<f64>, // 上午升到该高度
+    pub evening: Option<f64>, // 下午降到该高度
+    pub state: HorizonState, // 全天状态
+}
+
+impl std::fmt::Display for SolarAltitudeEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fmt = |x: Option<f64>| match x {
+            Some(jd) => jd2str(jd),
+            None => "--".to_string(),
+        };
+        let state = match self.state {
+            HorizonState::Normal => "",
+            HorizonState::Circumpolar => "(全天高于该高度)",
+            HorizonState::NeverRises => "(全天低于该高度)",
+        };
+        write!(
+            f,
+            "高度 {:.2}° 晨 {} 昏 {}{}",
+            self.alt.to_degrees(),
+            fmt(self.morning),
+            fmt(self.evening),
+            state
+        )
+    }
+}
+
+/// 晨昏蒙影
+///
+/// 各字段是太阳在对应高度的晨昏时刻:
+/// - 民用、航海、天文晨昏蒙影分别对应太阳高度-6°、-12°、-18°
+/// - `golden`: 太阳高度+6°, 上午从`blue`到`golden`、下午从`golden`到`blue`为黄金时刻
+/// - `blue`: 太阳高度-4°, 上午从`civil`到`blue`、下午从`blue`到`civil`为蓝调时刻
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Twilight {
+    pub civil: SolarAltitudeEvent, // 民用晨昏蒙影
+    pub nautical: SolarAltitudeEvent, // 航海晨昏蒙影
+    pub astronomical: SolarAltitudeEvent, // 天文晨昏蒙影
+    pub golden: SolarAltitudeEvent, // 黄金时刻边界
+    pub blue: SolarAltitudeEvent, // 蓝调时刻边界
+}
+
+impl std::fmt::Display for Twilight {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "民用 {}", self.civil)?;
+        writeln!(f, "航海 {}", self.nautical)?;
+        writeln!(f, "天文 {}", self.astronomical)?;
+        writeln!(f, "黄金时刻 {}", self.golden)?;
+        write!(f, "蓝调时刻 {}", self.blue)
+    }
+}
+
+/// 计算太阳高度事件
+///
+/// 计算本地日期当天太阳中心升到、降到指定高度的时刻，可用于自定义高度的晨昏计算
+///
+/// # Arguments
+///
+/// * `jd` - 当天任一时刻的儒略日（本地时间）或[`Instant`](crate::time::Instant)
+/// * `tz` - 时区， 比如东八区`tz=-8.0`
+/// * `lon` - 经度信息， 东经为正，弧度制
+/// * `lat` - 纬度信息，弧度制
+/// * `alt` - 太阳高度，弧度制
+///
+/// # Example
+/// 计算2023-7-23 北京民用晨光始、昏影终
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd = JulianDate::from_day(2023, 7, 23.0).jd;
+/// let lon = 116.383_f64.to_radians();
+/// let lat = 39.9_f64.to_radians();
+/// let r = solar_altitude_events(jd, -8.0, lon, lat, -6.0_f64.to_radians());
+/// println!("{}", r); // 高度 -6.00° 晨 2023-7-23 4h 33m 19.0s 昏 2023-7-23 20h 8m 4.0s
+/// ```
+pub fn solar_altitude_events(jd: impl Epoch, tz: f64, lon: f64, lat: f64, alt: f64) -> SolarAltitudeEvent {
+    let t0 = to_tt((jd.local(tz) + 0.5).floor() - 0.5, tz);
+    let (morning, evening, above) = rise_set::solar_altitude_events(t0, lon, lat, alt);
+    let state = match (morning, evening, above) {
+        (None, None, true) => HorizonState::Circumpolar,
+        (None, None, false) => HorizonState::NeverRises,
+        _ => HorizonState::Normal,
+    };
+    SolarAltitudeEvent {
+        alt,
+        morning: morning.map(|t| from_tt(t, tz)),
+        evening: evening.map(|t| from_tt(t, tz)),
+        state,
+    }
+}
+
+/// 计算晨昏蒙影以及黄金、蓝调时刻
+///
+/// 参数同[`solar_altitude_events`]
+///
+/// # Example
+/// 北纬60度夏至前后，天文昏影整夜不终
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd = JulianDate::from_day(2023, 6, 21.0).jd;
+/// let r = twilight(jd, -2.0, 10.75_f64.to_radians(), 60.0_f64.to_radians());
+/// println!("{}", r);
+/// assert_eq!(r.astronomical.state, HorizonState::Circumpolar);
+/// ```
+pub fn twilight(jd: impl Epoch, tz: f64, lon: f64, lat: f64) -> Twilight {
+    let ev = |deg: f64| solar_altitude_events(jd, tz, lon, lat, deg.to_radians());
+    Twilight {
+        civil: ev(-6.0),
+        nautical: ev(-12.0),
+        astronomical: ev(-18.0),
+        golden: ev(6.0),
+        blue: ev(-4.0),
+    }
+}
+
+/// 坐标中心
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Origin {
+    /// 日心
+    Heliocentric,
+    /// 地心
+    #[default]
+    Geocentric,
+    /// 站心，计入观测者的周日视差
+    Topocentric(Observer),
+}
+
+/// 位置的改正级别
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Reduction {
+    /// 几何位置，不计光行时
+    Geometric,
+    /// 天体测量位置，只计光行时
+    Astrometric,
+    /// 光行时加光行差
+    Aberrated,
+    /// 视位置，再加章动，与[`calculate_celestial_body`]的视坐标相同
+    #[default]
+    Apparent,
+}
+
+/// 指定中心和改正级别的位置
+///
+/// 黄道坐标为瞬时黄道，视位置取真春分点，其余取平春分点，距离统一为天文单位
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ReducedPosition {
+    pub body: CelestialBody, // 星体
+    pub origin: Origin, // 坐标中心
+    pub reduction: Reduction, // 改正级别
+    pub lon: f64, // 黄经
+    pub lat: f64, // 黄纬
+    pub dist: f64, // 距离，天文单位
+    pub ra: f64, // 赤经
+    pub dec: f64, // 赤纬
+}
+
+impl std::fmt::Display for ReducedPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "黄经 {} 黄纬 {} 距离 {:.8} 赤经 {} 赤纬 {}",
+            math_utils::Angle::from_f64(self.lon).degress(2),
+            math_utils::Angle::from_f64(self.lat).degress(2),
+            self.dist,
+            math_utils::Angle::from_f64(self.ra).time(2),
+            math_utils::Angle::from_f64(self.dec).degress(2)
+        )
+    }
+}
+
+/// 计算指定中心和改正级别的天体位置
+///
+/// 可以得到日心、地心、站心的几何位置、天体测量位置(光行时)、加光行差的位置以及视位置，
+/// 便于与历表逐项对照。日心坐标不计光行时和光行差，月亮的光行差已含在光行时中，四余没有日心坐标
+///
+/// # Arguments
+///
+/// * `body` - 星体
+/// * `jd` - 儒略日（本地时间）或[`Instant`](crate::time::Instant)
+/// * `tz` - 时区， 比如东八区`tz=-8.0`
+/// * `origin` - 坐标中心
+/// * `reduction` - 改正级别
+///
+/// # Example
+/// Meeus 例33.a，1992-12-20 0h 力学时 金星视赤经 21h04m41.45s
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::time::*;
+/// let t = Instant::new(2448976.5, TimeScale::TT);
+/// let p = position(CelestialBody::Venus, t, 0.0, Origin::Geocentric, Reduction::Apparent);
+/// println!("{}", p);
+/// assert!((p.ra.to_degrees() / 15.0 - 21.078182).abs() < 1e-4);
+///
+/// let h = position(CelestialBody::Venus, t, 0.0, Origin::Heliocentric, Reduction::Geometric);
+/// println!("日心 {}", h);
+/// ```
+pub fn position(body: CelestialBody, jd: impl Epoch, tz: f64, origin: Origin, reduction: Reduction) -> ReducedPosition {
+    let t = jd.tt(tz);
+    let (code, l, fa, high) = match origin {
+        Origin::Heliocentric => (0, 0.0, 0.0, 0.0),
+        Origin::Geocentric => (1, 0.0, 0.0, 0.0),
+        Origin::Topocentric(o) => (2, o.lon, o.lat, o.elevation / 1000.0),
+    };
+    let ((lon, lat, dist), (ra, dec)) = ephemeris::reduced_position(body as usize, t, code, reduction as usize, l, fa, high);
+    ReducedPosition { body, origin, reduction, lon, lat, dist, ra, dec }
+}
+
+/// 直角坐标的中心
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Center {
+    /// 日心
+    #[default]
+    Heliocentric,
+    /// 太阳系质心
+    Barycentric,
+}
+
+/// 直角坐标的参考架
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Frame {
+    /// J2000平黄道、平春分点
+    #[default]
+    EclipticJ2000,
+    /// J2000平赤道、平春分点，与ICRF相差不到0.1角秒
+    EquatorialJ2000,
+}
+
+/// 位置和速度
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct StateVector {
+    pub body: CelestialBody, // 星体
+    pub center: Center, // 中心
+    pub frame: Frame, // 参考架
+    pub position: (f64, f64, f64), // x, y, z，天文单位
+    pub velocity: (f64, f64, f64), // vx, vy, vz，天文单位/日
+}
+
+impl std::fmt::Display for StateVector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (p, v) = (self.position, self.velocity);
+        write!(
+            f,
+            "x {:.9} y {:.9} z {:.9} vx {:.9} vy {:.9} vz {:.9}",
+            p.0, p.1, p.2, v.0, v.1, v.2
+        )
+    }
+}
+
+/// 计算天体的J2000直角坐标位置和速度
+///
+/// 日期黄道坐标经岁差改正转为J2000黄道或赤道直角坐标，速度由位置差分得到。
+/// 太阳系质心坐标中太阳相对质心的位置由各行星质量加权得到，是近似值。月亮为日心(质心)坐标，
+/// 四余没有直角坐标
+///
+/// # Arguments
+///
+/// * `body` - 星体，地球到月亮
+/// * `jd` - 儒略日（本地时间）或[`Instant`](crate::time::Instant)
+/// * `tz` - 时区， 比如东八区`tz=-8.0`
+/// * `center` - 日心或太阳系质心
+/// * `frame` - J2000黄道或赤道
+///
+/// # Example
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd = JulianDate::from_day(2023, 7, 23.5).jd;
+/// let s = state_vector(CelestialBody::Jupiter, jd, -8.0, Center::Barycentric, Frame::EquatorialJ2000);
+/// println!("{}", s);
+/// let (x, y, z) = s.position;
+/// assert!(((x * x + y * y + z * z).sqrt() - 5.0).abs() < 0.2);
+///
+/// // 历表通常以TDB为自变量，与TT相差不到2毫秒。DE405：x=-0.18428AU
+/// use rust_ephemeris::time::*;
+/// let t = Instant::new(2451545.0, TimeScale::TDB);
+/// let v = state_vector(CelestialBody::Earth, t, 0.0, Center::Barycentric, Frame::EquatorialJ2000);
+/// assert!((v.position.0 + 0.1842).abs() < 1e-3);
+/// ```
+pub fn state_vector(body: CelestialBody, jd: impl Epoch, tz: f64, center: Center, frame: Frame) -> StateVector {
+    let (position, velocity) = ephemeris::state_vector(body as usize, jd.tt(tz), center as usize, frame as usize);
+    StateVector { body, center, frame, position, velocity }
+}
+
+/// 行星天象类型
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum PhenomenonKind {
+    /// 合(外行星)
+    #[default]
+    Conjunction,
+    /// 下合(内行星)
+    InferiorConjunction,
+    /// 上合(内行星)
+    SuperiorConjunction,
+    /// 冲(外行星)
+    Opposition,
+    /// 东大距(内行星)
+    GreatestElongationEast,
+    /// 西大距(内行星)
+    GreatestElongationWest,
+    /// 留，由顺行转为逆行
+    StationRetrograde,
+    /// 留，由逆行转为顺行
+    StationDirect,
+}
+
+impl From<usize> for PhenomenonKind {
+    fn from(value: usize) -> Self {
+        match value {
+            1 => PhenomenonKind::InferiorConjunction,
+            2 => PhenomenonKind::SuperiorConjunction,
+            3 => PhenomenonKind::Opposition,
+            4 => PhenomenonKind::GreatestElongationEast,
+            5 => PhenomenonKind::GreatestElongationWest,
+            6 => PhenomenonKind::StationRetrograde,
+            7 => PhenomenonKind::StationDirect,
+            _ => PhenomenonKind::Conjunction,
+        }
+    }
+}
+
+impl std::fmt::Display for PhenomenonKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            PhenomenonKind::Conjunction => "合",
+            PhenomenonKind::InferiorConjunction => "下合",
+            PhenomenonKind::SuperiorConjunction => "上合",
+            PhenomenonKind::Opposition => "冲",
+            PhenomenonKind::GreatestElongationEast => "东大距",
+            PhenomenonKind::GreatestElongationWest => "西大距",
+            PhenomenonKind::StationRetrograde => "留(顺转逆)",
+            PhenomenonKind::StationDirect => "留(逆转顺)",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// 行星天象
+///
+/// 合、冲以地心视黄经与太阳相同、相差180度为准，大距为距角极大，留为视黄经速度为零
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PlanetPhenomenon {
+    pub body: CelestialBody, // 星体
+    pub kind: PhenomenonKind, // 类型
+    pub jd: f64, // 本地时间儒略日
+    pub lon: f64, // 行星视黄经，弧度制
+    pub elongation: f64, // 距角，弧度制
+}
+
+impl std::fmt::Display for PlanetPhenomenon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {:?}{} 黄经 {} 距角 {}",
+            jd2str(self.jd),
+            self.body,
+            self.kind,
+            math_utils::Angle::from_f64(self.lon).degress(2),
+            math_utils::Angle::from_f64(self.elongation).degress(2)
+        )
+    }
+}
+
+/// 计算行星天象
+///
+/// 按时间顺序返回区间内行星的合、冲、东西大距以及留
+///
+/// # Arguments
+///
+/// * `body` - 水星到冥王星，其余返回空列表
+/// * `jd0`, `jd1` - 起止儒略日（本地时间）或[`Instant`](crate::time::Instant)
+/// * `tz` - 时区， 比如东八区`tz=-8.0`
+///
+/// # Example
+/// 2023年水星天象
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd0 = JulianDate::from_day(2023, 1, 1.0).jd;
+/// let jd1 = JulianDate::from_day(2024, 1, 1.0).jd;
+/// let r = planet_phenomena(CelestialBody::Mercury, jd0, jd1, -8.0);
+/// for p in r.iter() {
+///     println!("{}", p);
+/// }
+/// let n = r.iter().filter(|p| p.kind == PhenomenonKind::StationRetrograde).count();
+/// assert_eq!(n, 3);
+/// assert!(planet_phenomena(CelestialBody::Sun, jd0, jd1, -8.0).is_empty());
+/// ```
+pub fn planet_phenomena(body: CelestialBody, jd0: impl Epoch, jd1: impl Epoch, tz: f64) -> Vec<PlanetPhenomenon> {
+    let xt = body as usize;
+    if !(1..=8).contains(&xt) {
+        return Vec::new();
+    }
+    phenomena::planet_phenomena(xt, jd0.tt(tz), jd1.tt(tz))
+        .into_iter()
+        .map(|(t, kind, lon)| PlanetPhenomenon {
+            body,
+            kind: kind.into(),
+            jd: from_tt(t, tz),
+            lon,
+            elongation: phenomena::elongation(xt, t),
+        })
+        .collect()
+}
+
+/// 视坐标类型
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Coordinate {
+    /// 视黄经
+    #[default]
+    Longitude,
+    /// 视赤纬
+    Declination,
+    /// 视赤经
+    RightAscension,
+}
+
+/// 星体到达指定坐标的时刻
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Crossing {
+    pub body: CelestialBody, // 星体
+    pub coordinate: Coordinate, // 坐标类型
+    pub target: f64, // 目标坐标，弧度制
+    pub jd: f64, // 儒略日，本地时间
+    pub increasing: bool, // 到达时坐标是否增大，黄经减小即逆行
+}
+
+impl std::fmt::Display for Crossing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {:?} {:?} {}{}",
+            jd2str(self.jd),
+            self.body,
+            self.coordinate,
+            math_utils::Angle::from_f64(self.target).degress(2),
+            if self.increasing { "" } else { " (减小)" }
+        )
+    }
+}
+
+/// 计算星体视坐标到达指定值的时刻
+///
+/// 与[`qi_accurate2`](crate::lunnar::qi_accurate2)求太阳到达节气黄经类似，适用于任意星体以及视黄经、
+/// 视赤纬、视赤经，行星逆行时同一黄经可能多次到达，全部按时间顺序返回
+///
+/// # Arguments
+///
+/// * `body` - 星体，地球除外
+/// * `coordinate` - 坐标类型
+/// * `target` - 目标坐标，弧度制
+/// * `jd0`, `jd1` - 起止儒略日（本地时间）或[`Instant`](crate::time::Instant)
+/// * `tz` - 时区， 比如东八区`tz=-8.0`
+///
+/// # Example
+/// 2023年火星进入巨蟹座(黄经90度)
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd0 = JulianDate::from_day(2023, 1, 1.0).jd;
+/// let jd1 = JulianDate::from_day(2024, 1, 1.0).jd;
+/// let r = crossings(CelestialBody::Mars, Coordinate::Longitude, 90.0_f64.to_radians(), jd0, jd1, -8.0);
+/// for c in r.iter() {
+///     println!("{}", c);
+/// }
+/// assert_eq!(r.len(), 1);
+/// ```
+pub fn crossings(
+    body: CelestialBody,
+    coordinate: Coordinate,
+    target: f64,
+    jd0: impl Epoch,
+    jd1: impl Epoch,
+    tz: f64
+) -> Vec<Crossing> {
+    let xt = body as usize;
+    assert!(xt != 0, "crossings are not defined for the Earth");
+    phenomena::crossings(xt, coordinate as usize, target, jd0.tt(tz), jd1.tt(tz))
+        .into_iter()
+        .map(|(t, increasing)| Crossing { body, coordinate, target, jd: from_tt(t, tz), increasing })
+        .collect()
+}
+
+/// 计算星体视坐标下一次到达指定值的时刻
+///
+/// 从`jd`起向后搜索，最多搜索`years`年，用于太阳返照、月亮返照等计算
+///
+/// # Example
+/// 2023-03-21 18:30出生，2024年太阳返照
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd = JulianDate::from_day(2023, 3, 21.0 + 18.5 / 24.0).jd;
+/// let sun = calculate_celestial_body(CelestialBody::Sun, jd, -8.0, 2.12, 0.54);
+/// let r = next_crossing(CelestialBody::Sun, Coordinate::Longitude, sun.a_lon, jd + 1.0, -8.0, 2.0).unwrap();
+/// assert!((r.jd - jd - 365.25).abs() < 1.0);
+/// // 不超出搜索范围
+/// assert!(next_crossing(CelestialBody::Sun, Coordinate::Longitude, sun.a_lon, jd + 1.0, -8.0, 0.99).is_none());
+/// ```
+pub fn next_crossing(
+    body: CelestialBody,
+    coordinate: Coordinate,
+    target: f64,
+    jd: impl Epoch,
+    tz: f64,
+    years: f64
+) -> Option<Crossing> {
+    let xt = body as usize;
+    assert!(xt != 0, "crossings are not defined for the Earth");
+    let window = 60.0;
+    let t_start = jd.tt(tz);
+    let t_end = t_start + years * 365.25;
+    let mut t0 = t_start;
+    while t0 < t_end {
+        let t1 = (t0 + window).min(t_end);
+        if let Some((t, increasing)) = phenomena::crossings(xt, coordinate as usize, target, t0, t1).into_iter().next() {
+            return Some(Crossing { body, coordinate, target, jd: from_tt(t, tz), increasing });
+        }
+        t0 = t1;
+    }
+    None
+}
+
+/// 黄道十二星座，从白羊座开始
+pub const SIGNS: [&str; 12] = [
+    "白羊", "金牛", "双子", "巨蟹", "狮子", "处女", "天秤", "天蝎", "射手", "摩羯", "水瓶", "双鱼",
+];
+
+/// 二十八宿，从角宿开始
+pub const MANSIONS: [&str; 28] = [
+    "角", "亢", "氐", "房", "心", "尾", "箕", "斗", "牛", "女", "虚", "危", "室", "壁",
+    "奎", "娄", "胃", "昴", "毕", "觜", "参", "井", "鬼", "柳", "星", "张", "翼", "轸",
+];
+
+/// 入宫类型
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum IngressKind {
+    /// 进入回归黄道星座
+    #[default]
+    Sign,
+    /// 进入二十八宿
+    Mansion,
+}
+
+/// 入宫、入宿
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Ingress {
+    pub body: CelestialBody, // 星体
+    pub kind: IngressKind, // 星座或宿
+    pub index: usize, // 进入的星座(0-11，白羊起)或宿(0-27，角宿起)
+    pub jd: f64, // 儒略日，本地时间
+    pub retrograde: bool, // 是否逆行退入
+}
+
+impl Ingress {
+    /// 进入的星座或宿的名称
+    pub fn name(&self) -> &'static str {
+        match self.kind {
+            IngressKind::Sign => SIGNS[self.index],
+            IngressKind::Mansion => MANSIONS[self.index],
+        }
+    }
+}
+
+impl std::fmt::Display for Ingress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.kind {
+            IngressKind::Sign => "座",
+            IngressKind::Mansion => "宿",
+        };
+        write!(
+            f,
+            "{} {:?} {}入{}{}",
+            jd2str(self.jd),
+            self.body,
+            if self.retrograde { "逆行退" } else { "" },
+            self.name(),
+            kind
+        )
+    }
+}
+
+/// 计算星体进入星座和二十八宿的时刻
+///
+/// 星座按视黄经每30度划分；二十八宿为黄道宿度，以距星黄经(含岁差、章动)为各宿起点，
+/// 参宿距星取参宿一。逆行退回前一星座(宿)也会列出，按时间排序
+///
+/// # Arguments
+///
+/// * `body` - 星体，地球除外
+/// * `jd0`, `jd1` - 起止儒略日（本地时间）或[`Instant`](crate::time::Instant)
+/// * `tz` - 时区， 比如东八区`tz=-8.0`
+pub fn body_ingresses(body: CelestialBody, jd0: impl Epoch, jd1: impl Epoch, tz: f64) -> Vec<Ingress> {
+    let xt = body as usize;
+    assert!(xt != 0, "ingresses are not defined for the Earth");
+    let (t0, t1) = (jd0.tt(tz), jd1.tt(tz));
+    let mut r: Vec<Ingress> = Vec::new();
+    let signs = phenomena::ingresses(xt, 12, |i, _| (i as f64) * PI / 6.0, t0, t1)
+        .into_iter()
+        .map(|x| (x, IngressKind::Sign, 12));
+    let mansions = phenomena::ingresses(xt, 28, |i, t| xiu::xiu_lon(i, t) + ephemeris::nutation_lon2(t), t0, t1)
+        .into_iter()
+        .map(|x| (x, IngressKind::Mansion, 28));
+    for ((t, i, increasing), kind, n) in signs.chain(mansions) {
+        // 逆行经过分界点时进入的是前一个
+        let index = if increasing { i } else { (i + n - 1) % n };
+        r.push(Ingress { body, kind, index, jd: from_tt(t, tz), retrograde: !increasing });
+    }
+    r.sort_by(|a, b| a.jd.partial_cmp(&b.jd).unwrap());
+    r
+}
+
+/// 计算一年内日、月、行星进入星座和二十八宿的时刻
+///
+/// 包括太阳、月亮以及水星到冥王星，按时间排序
+///
+/// # Example
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// let r = ingresses(2023, -8.0);
+/// for x in r.iter().filter(|x| x.body == CelestialBody::Mercury) {
+///     println!("{}", x);
+/// }
+/// // 太阳入白羊即春分 2023-03-21 05:24(东八区)
+/// let x = r.iter().find(|x| x.body == CelestialBody::Sun && x.kind == IngressKind::Sign && x.index == 0).unwrap();
+/// println!("{}", x);
+/// // 水星逆行退入前一星座
+/// assert!(r.iter().any(|x| x.body == CelestialBody::Mercury && x.retrograde));
+/// ```
+pub fn ingresses(year: i32, tz: f64) -> Vec<Ingress> {
+    let jd0 = JulianDate::from_day(year, 1, 1.0).jd;
+    let jd1 = JulianDate::from_day(year + 1, 1, 1.0).jd;
+    let mut r: Vec<Ingress> = (1..=10)
+        .flat_map(|xt| body_ingresses(CelestialBody::from(xt), jd0, jd1, tz))
+        .collect();
+    r.sort_by(|a, b| a.jd.partial_cmp(&b.jd).unwrap());
+    r
+}
+
+/// 月亮天象类型
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum MoonEventKind {
+    /// 近地点
+    #[default]
+    Perigee,
+    /// 远地点
+    Apogee,
+    /// 升交点，月亮由南向北穿过黄道
+    AscendingNode,
+    /// 降交点，月亮由北向南穿过黄道
+    DescendingNode,
+    /// 赤纬北极值
+    MaxNorthDeclination,
+    /// 赤纬南极值
+    MaxSouthDeclination,
+}
+
+impl From<usize> for MoonEventKind {
+    fn from(value: usize) -> Self {
+        match value {
+            1 => MoonEventKind::Apogee,
+            2 => MoonEventKind::AscendingNode,
+            3 => MoonEventKind::DescendingNode,
+            4 => MoonEventKind::MaxNorthDeclination,
+            5 => MoonEventKind::MaxSouthDeclination,
+            _ => MoonEventKind::Perigee,
+        }
+    }
+}
+
+impl std::fmt::Display for MoonEventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            MoonEventKind::Perigee => "近地点",
+            MoonEventKind::Apogee => "远地点",
+            MoonEventKind::AscendingNode => "升交点",
+            MoonEventKind::DescendingNode => "降交点",
+            MoonEventKind::MaxNorthDeclination => "赤纬北极值",
+            MoonEventKind::MaxSouthDeclination => "赤纬南极值",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// 月亮天象
+///
+/// 近远地点以地心距极值为准，升降交点以视黄纬为零为准，赤纬极值为视赤纬极值
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MoonEvent {
+    pub kind: MoonEventKind, // 类型
+    pub jd: f64, // 本地时间儒略日
+    pub lon: f64, // 视黄经，弧度制
+    pub lat: f64, // 视黄纬，弧度制
+    pub dec: f64, // 视赤纬，弧度制
+    pub distance: f64, // 地心距，千米
+}
+
+impl std::fmt::Display for MoonEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} 黄经 {} 赤纬 {:.2}° 地心距 {:.0}千米",
+            jd2str(self.jd),
+            self.kind,
+            math_utils::Angle::from_f64(self.lon).degress(2),
+            self.dec.to_degrees(),
+            self.distance
+        )
+    }
+}
+
+/// 计算月亮天象
+///
+/// 按时间顺序返回区间内月亮的近地点、远地点、升降交点以及赤纬南北极值
+///
+/// # Arguments
+///
+/// * `jd0`, `jd1` - 起止儒略日（本地时间）或[`Instant`](crate::time::Instant)
+/// * `tz` - 时区， 比如东八区`tz=-8.0`
+///
+/// # Example
+/// 2023-8 月亮两次过近地点，8-31的望为超级月亮
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd0 = JulianDate::from_day(2023, 8, 1.0).jd;
+/// let r = moon_events(jd0, jd0 + 31.0, -8.0);
+/// for e in r.iter() {
+///     println!("{}", e);
+/// }
+/// let perigee: Vec<_> = r.iter().filter(|e| e.kind == MoonEventKind::Perigee).collect();
+/// assert_eq!(perigee.len(), 2);
+/// assert!(perigee[1].distance < 360000.0);
+/// ```
+pub fn moon_events(jd0: impl Epoch, jd1: impl Epoch, tz: f64) -> Vec<MoonEvent> {
+    phenomena::moon_events(jd0.tt(tz), jd1.tt(tz))
+        .into_iter()
+        .map(|(t, kind)| {
+            let (_, _, lon, lat, _, dec, _, distance, _, _, _, _, _, _, _) = compute_position(
+                10,
+                t,
+                0.0,
+                0.0
+            );
+            MoonEvent {
+                kind: kind.into(),
+                jd: from_tt(t, tz),
+                lon,
+                lat,
+                dec,
+                distance,
+            }
+        })
+        .collect()
+}
+
+/// 月面物理数据
+///
+/// 地心视位置，角度为弧度制
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MoonPhysical {
+    pub illuminated: f64, // 被照亮部分的比例，0-1
+    pub phase_angle: f64, // 相位角，日月对月面的张角，0为望
+    pub bright_limb: f64, // 亮边缘中点的位置角，从北向东量
+    pub age: f64, // 月龄，距上一次朔的日数
+    pub diameter: f64, // 视直径
+    pub libration_lon: f64, // 经天平动，正值表示东边缘(朝向危海)转入视野
+    pub libration_lat: f64, // 纬天平动，正值表示北边缘转入视野
+}
+
+impl std::fmt::Display for MoonPhysical {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "照亮 {:.1}% 相位角 {:.2}° 亮边缘 {:.1}° 月龄 {:.2}日 视直径 {:.1}' 天平动 {:.2}° {:.2}°",
+            self.illuminated * 100.0,
+            self.phase_angle.to_degrees(),
+            self.bright_limb.to_degrees(),
+            self.age,
+            self.diameter.to_degrees() * 60.0,
+            self.libration_lon.to_degrees(),
+            self.libration_lat.to_degrees()
+        )
+    }
+}
+
+/// 计算月面物理数据
+///
+/// 由日月视位置计算被照亮比例、相位角、亮边缘位置角、视直径和光学天平动，月龄从上一次朔起算
+///
+/// # Arguments
+///
+/// * `jd` - 儒略日（本地时间）或[`Instant`](crate::time::Instant)
+/// * `tz` - 时区， 比如东八区`tz=-8.0`
+///
+/// # Example
+/// 2023-8-31 09:35 望，月亮近地点附近，视直径大于33角分
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd = JulianDate::from_day(2023, 8, 31.0 + 9.6 / 24.0).jd;
+/// let m = moon_physical(jd, -8.0);
+/// println!("{}", m);
+/// assert!(m.illuminated > 0.99);
+/// assert!((m.age - 14.7).abs() < 0.1);
+/// assert!(m.diameter.to_degrees() * 60.0 > 33.0);
+///
+/// // 上弦月亮边缘朝西(位置角约270度)
+/// let jd = JulianDate::from_day(2023, 8, 24.0 + 17.95 / 24.0).jd;
+/// let m = moon_physical(jd, -8.0);
+/// assert!((m.illuminated - 0.5).abs() < 0.02);
+/// assert!((m.bright_limb.to_degrees() - 270.0).abs() < 30.0);
+/// ```
+pub fn moon_physical(jd: impl Epoch, tz: f64) -> MoonPhysical {
+    let (illuminated, phase_angle, bright_limb, age, diameter, libration_lon, libration_lat) =
+        physical::moon_physical(jd.tt(tz));
+    MoonPhysical { illuminated, phase_angle, bright_limb, age, diameter, libration_lon, libration_lat }
+}
+
+/// 行星视面数据
+///
+/// 地心视位置，角度为弧度制
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PlanetPhysical {
+    pub body: CelestialBody, // 行星
+    pub magnitude: f64, // 目视星等，土星计入光环
+    pub elongation: f64, // 距角，与太阳的角距
+    pub east: bool, // 是否在太阳以东(昏星)
+    pub phase_angle: f64, // 相位角，从行星看日地的张角
+    pub illuminated: f64, // 被照亮部分的比例，0-1
+    pub diameter: f64, // 赤道视直径
+}
+
+impl std::fmt::Display for PlanetPhysical {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} 星等 {:.1} 距角 {:.1}°{} 相位角 {:.1}° 照亮 {:.1}% 视直径 {:.1}\"",
+            self.body,
+            self.magnitude,
+            self.elongation.to_degrees(),
+            if self.east { "东" } else { "西" },
+            self.phase_angle.to_degrees(),
+            self.illuminated * 100.0,
+            self.diameter.to_degrees() * 3600.0
+        )
+    }
+}
+
+/// 计算行星视面数据
+///
+/// 星等、距角、相位角、被照亮比例和赤道视直径，由日心距、地心距得到
+///
+/// # Arguments
+///
+/// * `body` - 水星到冥王星，其余返回`None`
+/// * `jd` - 儒略日（本地时间）或[`Instant`](crate::time::Instant)
+/// * `tz` - 时区， 比如东八区`tz=-8.0`
+///
+/// # Example
+/// 2023-8-13 金星下合前后，相位很细，视直径接近1角分
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd = JulianDate::from_day(2023, 8, 13.0).jd;
+/// let v = planet_physical(CelestialBody::Venus, jd, -8.0).unwrap();
+/// println!("{}", v);
+/// assert!(v.illuminated < 0.05);
+/// assert!(v.diameter.to_degrees() * 3600.0 > 55.0);
+///
+/// // 2023-8-27 土星冲日，星等约0.4
+/// let jd = JulianDate::from_day(2023, 8, 27.0).jd;
+/// let s = planet_physical(CelestialBody::Saturn, jd, -8.0).unwrap();
+/// assert!(s.elongation.to_degrees() > 170.0);
+/// assert!((s.magnitude - 0.4).abs() < 0.2);
+/// assert!(planet_physical(CelestialBody::Moon, jd, -8.0).is_none());
+/// ```
+pub fn planet_physical(body: CelestialBody, jd: impl Epoch, tz: f64) -> Option<PlanetPhysical> {
+    let xt = body as usize;
+    if !(1..=8).contains(&xt) {
+        return None;
+    }
+    let (magnitude, elongation, east, phase_angle, illuminated, diameter) = physical::planet_physical(
+        xt,
+        jd.tt(tz)
+    );
+    Some(PlanetPhysical { body, magnitude, elongation, east, phase_angle, illuminated, diameter })
+}
+
+/// 宫制
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum HouseSystem {
+    /// 普拉西度制，按半昼弧、半夜弧三分时间
+    #[default]
+    Placidus = 0,
+    /// 科赫制，按中天度数的半昼弧三分时间
+    Koch = 1,
+    /// 雷格蒙塔努斯制，等分天赤道
+    Regiomontanus = 2,
+    /// 坎帕努斯制，等分卯酉圈
+    Campanus = 3,
+    /// 波菲利制，四个象限各自三等分黄经
+    Porphyry = 4,
+    /// 等宫制，从上升点起每宫30度
+    Equal = 5,
+    /// 整宫制，上升点所在星座为第1宫
+    WholeSign = 6,
+    /// 阿卡比特斯制，按上升点度数的半昼弧三分赤经
+    Alcabitius = 7,
+    /// 莫里努斯制，从中天赤经起等分天赤道
+    Morinus = 8,
+}
+
+impl From<usize> for HouseSystem {
+    fn from(value: usize) -> Self {
+        match value {
+            1 => HouseSystem::Koch,
+            2 => HouseSystem::Regiomontanus,
+            3 => HouseSystem::Campanus,
+            4 => HouseSystem::Porphyry,
+            5 => HouseSystem::Equal,
+            6 => HouseSystem::WholeSign,
+            7 => HouseSystem::Alcabitius,
+            8 => HouseSystem::Morinus,
+            _ => HouseSystem::Placidus,
+        }
+    }
+}
+
+impl std::fmt::Display for HouseSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            HouseSystem::Placidus => "普拉西度制",
+            HouseSystem::Koch => "科赫制",
+            HouseSystem::Regiomontanus => "雷格蒙塔努斯制",
+            HouseSystem::Campanus => "坎帕努斯制",
+            HouseSystem::Porphyry => "波菲利制",
+            HouseSystem::Equal => "等宫制",
+            HouseSystem::WholeSign => "整宫制",
+            HouseSystem::Alcabitius => "阿卡比特斯制",
+            HouseSystem::Morinus => "莫里努斯制",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// 十二宫宫头
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct HouseCusps {
+    /// 请求的宫制
+    pub system: HouseSystem,
+    /// 实际使用的宫制。普拉西度、科赫、阿卡比特斯制在极区(黄道部分度数拱极或永不升起)可能无解，
+    /// 此时改用波菲利制
+    pub used: HouseSystem,
+    /// 第1宫到第12宫宫头黄经，弧度制
+    pub cusps: [f64; 12],
+}
+
+impl HouseCusps {
+    /// 是否因极区无解改用了波菲利制
+    pub fn fallback(&self) -> bool {
+        self.system != self.used
+    }
+}
+
+impl std::fmt::Display for HouseCusps {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.system)?;
+        if self.fallback() {
+            write!(f, "(极区无解，改用{})", self.used)?;
+        }
+        for (i, c) in self.cusps.iter().enumerate() {
+            write!(f, "\n第{}宫: {:.2}°", i + 1, c.to_degrees())?;
+        }
+        Ok(())
+    }
+}
+
+/// 盘中的点，用于阿拉伯点等计算
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ChartPoint {
+    /// 星体
+    Body(CelestialBody),
+    /// 上升点
+    Asc,
+    /// 中天
+    Mc,
+    /// 下降点
+    Dsc,
+    /// 天底
+    Ic,
+    /// 宿命点
+    Vertex,
+    /// 宫头，宫制及宫位序号(1-12)
+    Cusp(HouseSystem, usize),
+    /// 固定黄经，弧度制
+    Lon(f64),
+}
+
+/// 阿拉伯点
+///
+/// 黄经为 `a + b - c`，`reverse_at_night`为真时夜间盘交换`b`、`c`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ArabicPart {
+    pub a: ChartPoint,
+    pub b: ChartPoint,
+    pub c: ChartPoint,
+    pub reverse_at_night: bool,
+}
+
+impl ArabicPart {
+    /// 福点
+    pub const FORTUNE: ArabicPart = ArabicPart {
+        a: ChartPoint::Asc,
+        b: ChartPoint::Body(CelestialBody::Moon),
+        c: ChartPoint::Body(CelestialBody::Sun),
+        reverse_at_night: true,
+    };
+
+    /// 精神点
+    pub const SPIRIT: ArabicPart = ArabicPart {
+        a: ChartPoint::Asc,
+        b: ChartPoint::Body(CelestialBody::Sun),
+        c: ChartPoint::Body(CelestialBody::Moon),
+        reverse_at_night: true,
+    };
+
+    /// 昼夜相同的阿拉伯点 a + b - c
+    pub fn new(a: ChartPoint, b: ChartPoint, c: ChartPoint) -> Self {
+        ArabicPart { a, b, c, reverse_at_night: false }
+    }
+
+    /// 夜间交换b、c的阿拉伯点
+    pub fn with_sect(a: ChartPoint, b: ChartPoint, c: ChartPoint) -> Self {
+        ArabicPart { a, b, c, reverse_at_night: true }
+    }
+}
+
 /// 占星主要宫位计算
 ///
 /// 在西洋占星中，主要有太阳、月亮以及九大行星等天体
@@ -225,6 +1784,11 @@ pub fn calculate_celestial_body(
 /// 此外还有一些重要概念，比如Asc(上升点)， Mc(中天)，借用术数概念将这些概念称为虚星
 /// 上升点是指在天球上东方地平线与天球相交处的点，和术数中命宫概念相同
 ///
+/// 与[`calculate_celestial_body`]一样，时间加ΔT转为力学时，恒星时取含章动的真恒星时，
+/// 黄赤交角取真黄赤交角，因此宫位与行星位置在同一坐标系中
+///
+/// [`Hourse::new`]沿用原来的约定：`jd`为世界时，经度东经为负；
+/// 按本地时间和东经为正的经度构造用[`Hourse::from_local`]
 ///
 /// # Example
 /// 
@@ -234,11 +1798,11 @@ pub fn calculate_celestial_body(
 ///use rust_ephemeris::astronomy::*;
 ///use rust_ephemeris::{JulianDate, math_utils};
 /// 
-/// let jd = JulianDate::from_day(2023, 3,21.0+10.5/24.0).jd;
+/// let jd = JulianDate::from_day(2023, 3, 21.0 + 18.5 / 24.0).jd;
 /// 
-/// let lon = -121.45/180.0*PI;
+/// let lon = 121.45/180.0*PI;
 /// let lat = 31.216666666666665/180.0 *PI;
-/// let mut h = Hourse::new(jd, -8.0, lon, lat);
+/// let mut h = Hourse::from_local(jd, -8.0, lon, lat);
 /// 
 /// println!("T={}", h.t());
 /// println!("RA={}", math_utils::Angle::from_f64(h.ra()).degress(2));
@@ -250,60 +1814,124 @@ pub fn calculate_celestial_body(
 /// 
 /// // 中天点计算
 /// println!("MC={}", math_utils::Angle::from_f64(h.mc()).degress(2));
-/// 
+///
+/// // 同一时刻用世界时和东经为负的经度构造
+/// let jd_ut = JulianDate::from_day(2023, 3, 21.0 + 10.5 / 24.0).jd;
+/// let mut h2 = Hourse::new(jd_ut, -8.0, -lon, lat);
+/// assert!((h2.asc() - h.asc()).abs() < 1e-9);
+///
+/// // 地方恒星时与行星计算使用的真恒星时在角秒以内一致
+/// let sun = calculate_celestial_body(CelestialBody::Sun, jd, -8.0, lon, lat);
+/// let d = math_utils::rad2rrad(h.ra() - sun.sid_time.1 - lon);
+/// assert!(d.abs().to_degrees() * 3600.0 < 0.1);
 /// ```
 #[derive(Debug, Default, Copy, Clone, PartialEq)]
 pub struct Hourse {
-    pub jd: f64, // 儒略日， UT时间,
-    pub tz: f64, // 时区
-    pub lon: f64, // 经度
-    pub lat: f64, // 纬度
+    pub jd: f64, // 儒略日， UT时间
+    pub tz: f64, // 时区，仅作记录，jd已是世界时
+    pub lon: f64, // 经度，东经为负，弧度制
+    pub lat: f64, // 纬度，弧度制
 
     _ra: Option<f64>,
+    _ob: Option<f64>, // 真黄赤交角
     _t: Option<f64>, // 儒略世纪， 相对于J1900
+    _tt: Option<f64>, // 力学时，J2000起算的日数
     _asc: Option<f64>, // 上升星座经度，弧度制
     _ep: Option<f64>, // 东升点计算
     _mc: Option<f64>, // 中天计算
 }
 
 impl Hourse {
+    /// 由世界时构造
+    ///
+    /// # Arguments
+    ///
+    /// * `jd` - 儒略日，世界时
+    /// * `tz` - 时区，仅作记录
+    /// * `lon` - 经度，东经为负，弧度制
+    /// * `lat` - 纬度，弧度制
     pub fn new(jd: f64, tz: f64, lon: f64, lat: f64) -> Self {
-        Self { jd: jd, tz: tz, lon: lon, lat: lat, ..Default::default() }
+        Self { jd, tz, lon, lat, ..Default::default() }
+    }
+
+    /// 由本地时间构造，与[`calculate_celestial_body`]等接口的约定相同
+    ///
+    /// # Arguments
+    ///
+    /// * `jd` - 儒略日，本地时间，或[`Instant`](crate::time::Instant)
+    /// * `tz` - 时区， 比如东八区`tz=-8.0`
+    /// * `lon` - 经度，东经为正，弧度制
+    /// * `lat` - 纬度，弧度制
+    ///
+    /// # Example
+    /// ```
+    /// use rust_ephemeris::astronomy::*;
+    /// use rust_ephemeris::time::*;
+    /// let t = Instant::from_day(2023, 3, 21.0 + 10.5 / 24.0, TimeScale::UTC);
+    /// let mut a = Hourse::from_local(t, -8.0, 2.12, 0.54);
+    /// let mut b = Hourse::from_local(t.to_local(-8.0), -8.0, 2.12, 0.54);
+    /// // Instant按闰秒表换算，f64按ΔT模型换算，两者相差约1秒
+    /// assert!((a.asc() - b.asc()).abs().to_degrees() < 0.02);
+    /// ```
+    pub fn from_local(jd: impl Epoch, tz: f64, lon: f64, lat: f64) -> Self {
+        let mut h = Self::new(jd.local(tz) + tz / 24.0, tz, -lon, lat);
+        h._tt = Some(jd.tt(tz));
+        h
     }
     
-    /// 儒略世纪相对于1900年1月1日
+    /// 儒略世纪相对于1900年1月1日，世界时
     pub fn t(&mut self)->f64{
         match self._t {
             Some(a)=>a,
-            None=>{      
-            
-                let _t = (self.jd - 2415020.0)/36525.0;
-
+            None=>{
+                let _t = (self.jd - 2415020.0) / 36525.0;
                 self._t = Some(_t);
                 _t
+            }
+        }
+    }
+
+    // 同一地点`days`日后的盘
+    pub(crate) fn shifted(&self, days: f64) -> Self {
+        let mut h = Self::new(self.jd + days, self.tz, self.lon, self.lat);
+        h._tt = self._tt.map(|t| t + days);
+        h
+    }
 
+    // 力学时，J2000起算的日数
+    fn tt(&mut self) -> f64 {
+        match self._tt {
+            Some(a) => a,
+            None => {
+                let tt = to_tt(self.jd, 0.0);
+                self._tt = Some(tt);
+                tt
             }
         }
     }
 
+    // 计算地方真恒星时和真黄赤交角
+    fn sidereal(&mut self) -> (f64, f64) {
+        if let (Some(ra_), Some(ob)) = (self._ra, self._ob) {
+            return (ra_, ob);
+        }
+        let (gst, ob) = ephemeris::true_sidereal(self.tt());
+        let ra_ = math_utils::rad2mrad(gst - self.lon);
+        self._ra = Some(ra_);
+        self._ob = Some(ob);
+        (ra_, ob)
+    }
+
     /// 赤经计算
     /// 
-    /// 计算赤经，采用弧度制
+    /// 中天赤经，即地方真恒星时，采用弧度制
     pub fn ra(&mut self)->f64{
-        if let Some(ra_) = self._ra{
-            return ra_;
-        }
-        let _t = self.t();
-
-        let tmp_jd = self.jd  +0.5;
-        let tim = (tmp_jd - tmp_jd.floor())*24.0; //获取时间（小时制）
-        let lon = self.lon/PI * 180.0;
+        self.sidereal().0
+    }
 
-        let mut ra1= (6.6460656 + 2400.0513 * _t + 2.58E-5 * _t * _t + tim) * 15.0 - lon; // 角度制
-        
-        ra1 = math_utils::Angle::from_f64(ra1/180.0*PI).rad; // 弧度制下的ra
-        self._ra = Some(ra1);
-        ra1
+    /// 真黄赤交角，弧度制
+    pub fn obliquity(&mut self) -> f64 {
+        self.sidereal().1
     }
 
     /// 中天计算
@@ -312,19 +1940,10 @@ impl Hourse {
         if let Some(mc_) = self._mc{
             return mc_;
         }
-        let ob = obliquity(self.jd);
-        let  ra_ = self.ra();
-        let m = ra_.tan();
-        let n = ob.cos();
-       
-        let mut mc_ =m.atan2(n);
-        if ra_>PI/2.0 && ra_ < 3.0*PI/2.0{
-            mc_ += PI;
-        }
-        mc_ = math_utils::Angle::from_f64(mc_).rad;
+        let (ra_, ob) = self.sidereal();
+        let mc_ = houses::mc(ra_, ob);
         self._mc=Some(mc_);
         mc_
-
     }
 
 
@@ -335,14 +1954,8 @@ impl Hourse {
         if let Some(asc_) = self._asc{
             return asc_;
         }
-        let ob = obliquity(self.jd);
-        let ra_ = self.ra();
-        let lat = self.lat;
-        let m = - ra_.sin() * ob.cos() - lat.tan() * ob.sin();
-        let n = ra_.cos();
-
-        let mut asc_ = n.atan2(m);
-        asc_ = math_utils::Angle::from_f64(asc_).rad;
+        let (ra_, ob) = self.sidereal();
+        let asc_ = houses::asc(ra_, ob, self.lat);
         self._asc = Some(asc_);
         asc_
     }
@@ -353,17 +1966,124 @@ impl Hourse {
         if let Some(ep_) = self._ep{
             return ep_;
         }
-        let ob = obliquity(self.jd);
-        let ra_ = self.ra();
-        let m =  - ra_.sin() * ob.cos();
-        let n = ra_.cos();
-
-        let mut ep_ = n.atan2(m);
-        ep_ = math_utils::Angle::from_f64(ep_).rad;
+        let (ra_, ob) = self.sidereal();
+        let ep_ = houses::asc(ra_, ob, 0.0);
         self._ep = Some(ep_);
         ep_
     }
 
+    /// 十二宫宫头计算
+    ///
+    /// 普拉西度、科赫、阿卡比特斯制需要黄道度数的半昼弧，在极区可能无解，这时改用波菲利制，
+    /// 并在返回值的`used`中注明
+    ///
+    /// # Example
+    /// ```
+    /// use std::f64::consts::PI;
+    /// use rust_ephemeris::astronomy::*;
+    /// use rust_ephemeris::JulianDate;
+    ///
+    /// let jd = JulianDate::from_day(2023, 3, 21.0 + 18.5 / 24.0).jd;
+    /// let mut h = Hourse::from_local(jd, -8.0, 121.45 / 180.0 * PI, 31.216666666666665 / 180.0 * PI);
+    /// let c = h.cusps(HouseSystem::Placidus);
+    /// assert!(!c.fallback());
+    /// assert!((c.cusps[0] - h.asc()).abs() < 1e-9);
+    /// println!("{}", c);
+    ///
+    /// // 北纬70度普拉西度制无解
+    /// let mut h = Hourse::from_local(jd, -8.0, 0.0, 70.0 / 180.0 * PI);
+    /// let c = h.cusps(HouseSystem::Placidus);
+    /// assert_eq!(c.used, HouseSystem::Porphyry);
+    /// ```
+    pub fn cusps(&mut self, system: HouseSystem) -> HouseCusps {
+        let (ra_, ob) = self.sidereal();
+        let (used, cusps) = match houses::house_cusps(system as usize, ra_, ob, self.lat) {
+            Some(c) => (system, c),
+            None => (HouseSystem::Porphyry, houses::house_cusps(HouseSystem::Porphyry as usize, ra_, ob, self.lat).unwrap()),
+        };
+        HouseCusps { system, used, cusps }
+    }
+
+    /// 宿命点(Vertex)，卯酉圈与黄道在西方的交点，弧度制
+    pub fn vertex(&mut self) -> f64 {
+        let (ra_, ob) = self.sidereal();
+        houses::vertex(ra_, ob, self.lat)
+    }
+
+    /// 反宿命点(Anti-Vertex)，与宿命点相对
+    pub fn anti_vertex(&mut self) -> f64 {
+        math_utils::rad2mrad(self.vertex() + PI)
+    }
+
+    /// 是否白天盘，即太阳在地平线以上(位于第7宫到第12宫)
+    pub fn is_day(&mut self) -> bool {
+        let sun = self.point(ChartPoint::Body(CelestialBody::Sun));
+        math_utils::rad2mrad(sun - self.asc()) >= PI
+    }
+
+    /// 盘中各点的黄经，星体取视黄经，弧度制
+    pub fn point(&mut self, p: ChartPoint) -> f64 {
+        match p {
+            ChartPoint::Body(body) => {
+                let t = self.tt();
+                compute_position(body as usize, t, -self.lon, self.lat).2
+            }
+            ChartPoint::Asc => self.asc(),
+            ChartPoint::Mc => self.mc(),
+            ChartPoint::Dsc => math_utils::rad2mrad(self.asc() + PI),
+            ChartPoint::Ic => math_utils::rad2mrad(self.mc() + PI),
+            ChartPoint::Vertex => self.vertex(),
+            ChartPoint::Cusp(system, n) => {
+                assert!((1..=12).contains(&n), "house number must be 1-12");
+                self.cusps(system).cusps[n - 1]
+            }
+            ChartPoint::Lon(x) => math_utils::rad2mrad(x),
+        }
+    }
+
+    /// 计算阿拉伯点，黄经为 A + B - C，夜间盘按需要交换B、C
+    ///
+    /// # Example
+    /// 自定义婚姻点：上升 + 下降 - 金星
+    /// ```
+    /// use rust_ephemeris::astronomy::*;
+    /// use rust_ephemeris::JulianDate;
+    /// let jd = JulianDate::from_day(2023, 3, 21.5).jd;
+    /// let mut h = Hourse::from_local(jd, -8.0, 2.12, 0.54);
+    /// let marriage = ArabicPart::new(ChartPoint::Asc, ChartPoint::Dsc, ChartPoint::Body(CelestialBody::Venus));
+    /// println!("婚姻点 {:.2}°", h.arabic_part(&marriage).to_degrees());
+    /// ```
+    pub fn arabic_part(&mut self, part: &ArabicPart) -> f64 {
+        let (b, c) = if part.reverse_at_night && !self.is_day() {
+            (part.c, part.b)
+        } else {
+            (part.b, part.c)
+        };
+        math_utils::rad2mrad(self.point(part.a) + self.point(b) - self.point(c))
+    }
+
+    /// 福点：白天为 上升 + 月亮 - 太阳，夜间太阳、月亮互换
+    ///
+    /// # Example
+    /// ```
+    /// use rust_ephemeris::astronomy::*;
+    /// use rust_ephemeris::{JulianDate, math_utils};
+    /// let jd = JulianDate::from_day(2023, 3, 21.0 + 18.5 / 24.0).jd;
+    /// let mut h = Hourse::from_local(jd, -8.0, 2.12, 0.54);
+    /// assert!(!h.is_day());
+    /// // 福点与精神点关于上升点对称
+    /// let d = math_utils::rad2rrad(h.fortune() + h.spirit() - 2.0 * h.asc());
+    /// assert!(d.abs() < 1e-9);
+    /// ```
+    pub fn fortune(&mut self) -> f64 {
+        self.arabic_part(&ArabicPart::FORTUNE)
+    }
+
+    /// 精神点：白天为 上升 + 太阳 - 月亮，夜间太阳、月亮互换
+    pub fn spirit(&mut self) -> f64 {
+        self.arabic_part(&ArabicPart::SPIRIT)
+    }
+
 
 
 }
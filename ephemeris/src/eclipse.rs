@@ -0,0 +1,455 @@
+//! 日月食计算
+//!
+//! 基于内部的日月位置序列计算日食、月食的全局情况(类型、γ、食分、各食相时刻)以及观测地的地方情况,
+//! 时间都是本地时间的儒略日，时区`tz`的约定与[`astronomy`](crate::astronomy)一致，比如东八区`tz=-8.0`
+
+use crate::internal::{ constants, eclipse, lunnar, math_utils };
+use crate::internal::ephemeris::compute_position;
+use crate::astronomy::{ from_tt, jd2str, to_tt };
+use crate::time::Epoch;
+
+/// 日食类型
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum SolarEclipseKind {
+    /// 偏食
+    #[default]
+    Partial,
+    /// 环食
+    Annular,
+    /// 全食
+    Total,
+    /// 全环食
+    Hybrid,
+}
+
+impl From<usize> for SolarEclipseKind {
+    fn from(value: usize) -> Self {
+        match value {
+            1 => SolarEclipseKind::Annular,
+            2 => SolarEclipseKind::Total,
+            3 => SolarEclipseKind::Hybrid,
+            _ => SolarEclipseKind::Partial,
+        }
+    }
+}
+
+impl std::fmt::Display for SolarEclipseKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            SolarEclipseKind::Partial => "偏食",
+            SolarEclipseKind::Annular => "环食",
+            SolarEclipseKind::Total => "全食",
+            SolarEclipseKind::Hybrid => "全环食",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// 日食全局情况
+///
+/// `jd`是全球食甚(影轴离地心最近)的本地时间，`gamma`以地球赤道半径为单位，影轴在地心北侧为正；
+/// 中心食的`magnitude`是日月视直径比，偏食是食甚点的最大食分
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SolarEclipse {
+    pub kind: SolarEclipseKind, // 类型
+    pub jd: f64, // 食甚，本地时间儒略日
+    pub tz: f64, // 时区
+    pub gamma: f64, // γ
+    pub magnitude: f64, // 食分
+    pub central: bool, // 是否中心食
+    pub lunation: i32, // 月相序数(Meeus)
+}
+
+impl std::fmt::Display for SolarEclipse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} 日{} γ={:.4} 食分={:.4}",
+            jd2str(self.jd),
+            self.kind,
+            self.gamma,
+            self.magnitude
+        )
+    }
+}
+
+/// 食相时刻
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EclipseContact {
+    pub jd: f64, // 本地时间儒略日
+    pub alt: f64, // 观测地太阳(月食为月亮)视高度，弧度制，负值表示在地平之下
+}
+
+impl std::fmt::Display for EclipseContact {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} 高度 {:.2}°", jd2str(self.jd), self.alt.to_degrees())
+    }
+}
+
+/// 日食地方情况
+///
+/// 食既、生光只在观测地看到全食或环食时存在。各时刻按几何关系计算，
+/// 太阳在地平之下的部分实际看不到，需结合`alt`判断
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LocalSolarEclipse {
+    pub kind: SolarEclipseKind, // 观测地看到的类型，不会是全环食
+    pub c1: EclipseContact, // 初亏
+    pub c2: Option<EclipseContact>, // 食既
+    pub max: EclipseContact, // 食甚
+    pub c3: Option<EclipseContact>, // 生光
+    pub c4: EclipseContact, // 复圆
+    pub magnitude: f64, // 食分，日面直径被遮比例
+    pub obscuration: f64, // 日面面积被遮比例
+}
+
+impl std::fmt::Display for LocalSolarEclipse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "日{} 食分={:.4} 食面积比={:.4}",
+            self.kind,
+            self.magnitude,
+            self.obscuration
+        )?;
+        writeln!(f, "初亏 {}", self.c1)?;
+        if let Some(c) = self.c2 {
+            writeln!(f, "食既 {}", c)?;
+        }
+        writeln!(f, "食甚 {}", self.max)?;
+        if let Some(c) = self.c3 {
+            writeln!(f, "生光 {}", c)?;
+        }
+        write!(f, "复圆 {}", self.c4)
+    }
+}
+
+impl SolarEclipse {
+    /// 计算日食在观测地的地方情况，观测地不在半影区内返回`None`
+    ///
+    /// # Arguments
+    ///
+    /// * `lon` - 经度信息， 东经为正，弧度制
+    /// * `lat` - 纬度信息，弧度制
+    pub fn local(&self, lon: f64, lat: f64) -> Option<LocalSolarEclipse> {
+        let tz = self.tz;
+        let (c, magnitude, obscuration, kind) = eclipse::local_solar_eclipse(
+            to_tt(self.jd, tz),
+            lon,
+            lat
+        )?;
+        let contact = |t: f64| EclipseContact {
+            jd: from_tt(t, tz),
+            alt: eclipse::topo_sun_moon(t, lon, lat).2,
+        };
+        Some(LocalSolarEclipse {
+            kind: kind.into(),
+            c1: contact(c[0]?),
+            c2: c[1].map(contact),
+            max: contact(c[2]?),
+            c3: c[3].map(contact),
+            c4: contact(c[4]?),
+            magnitude,
+            obscuration,
+        })
+    }
+}
+
+/// 计算第`k`个朔望月(Meeus月相序数)的日食，没有日食返回`None`
+pub fn solar_eclipse(k: i32, tz: f64) -> Option<SolarEclipse> {
+    let (t, kind, gamma, magnitude, central) = eclipse::solar_eclipse(k)?;
+    Some(SolarEclipse {
+        kind: kind.into(),
+        jd: from_tt(t, tz),
+        tz,
+        gamma,
+        magnitude,
+        central,
+        lunation: k,
+    })
+}
+
+/// 搜索区间内的日食
+///
+/// # Arguments
+///
+/// * `jd0`, `jd1` - 起止儒略日（本地时间）或[`Instant`](crate::time::Instant)
+/// * `tz` - 时区， 比如东八区`tz=-8.0`
+///
+/// # Example
+/// 2024年的日食以及2024-4-8日全食在达拉斯的地方情况
+/// ```
+/// use rust_ephemeris::eclipse::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd0 = JulianDate::from_day(2024, 1, 1.0).jd;
+/// let jd1 = JulianDate::from_day(2025, 1, 1.0).jd;
+/// let r = solar_eclipses(jd0, jd1, 0.0);
+/// assert_eq!(r.len(), 2);
+/// assert_eq!(r[0].kind, SolarEclipseKind::Total);
+/// assert_eq!(r[1].kind, SolarEclipseKind::Annular);
+/// println!("{}", r[0]); // 2024-4-8 18h 17m 17.0s 日全食 γ=0.3431 食分=1.0566
+///
+/// let local = r[0].local(-96.797_f64.to_radians(), 32.777_f64.to_radians()).unwrap();
+/// println!("{}", local);
+/// assert_eq!(local.kind, SolarEclipseKind::Total);
+/// assert!(local.c2.is_some() && local.max.alt > 0.0);
+/// ```
+pub fn solar_eclipses(jd0: impl Epoch, jd1: impl Epoch, tz: f64) -> Vec<SolarEclipse> {
+    let (jd0, jd1) = (jd0.local(tz), jd1.local(tz));
+    // 月相序数按东八区计算
+    let k0 = lunnar::lunation_number(jd0 - constants::J2000 + (tz + 8.0) / 24.0);
+    let k1 = lunnar::lunation_number(jd1 - constants::J2000 + (tz + 8.0) / 24.0);
+    (k0..=k1)
+        .filter_map(|k| solar_eclipse(k, tz))
+        .filter(|e| e.jd >= jd0 && e.jd < jd1)
+        .collect()
+}
+
+/// 月食类型
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum LunarEclipseKind {
+    /// 半影月食
+    #[default]
+    Penumbral,
+    /// 月偏食
+    Partial,
+    /// 月全食
+    Total,
+}
+
+impl From<usize> for LunarEclipseKind {
+    fn from(value: usize) -> Self {
+        match value {
+            1 => LunarEclipseKind::Partial,
+            2 => LunarEclipseKind::Total,
+            _ => LunarEclipseKind::Penumbral,
+        }
+    }
+}
+
+impl std::fmt::Display for LunarEclipseKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            LunarEclipseKind::Penumbral => "半影月食",
+            LunarEclipseKind::Partial => "月偏食",
+            LunarEclipseKind::Total => "月全食",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// 月食
+///
+/// 各食相时刻为本地时间儒略日，地影按Danjon法(地球半径增大1/85)计算。
+/// 初亏、复圆只在本影食时存在，食既、生光只在月全食时存在
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LunarEclipse {
+    pub kind: LunarEclipseKind, // 类型
+    pub p1: f64, // 半影食始
+    pub u1: Option<f64>, // 初亏
+    pub u2: Option<f64>, // 食既
+    pub max: f64, // 食甚
+    pub u3: Option<f64>, // 生光
+    pub u4: Option<f64>, // 复圆
+    pub p4: f64, // 半影食终
+    pub umbral_magnitude: f64, // 本影食分
+    pub penumbral_magnitude: f64, // 半影食分
+    pub tz: f64, // 时区
+    pub lunation: i32, // 月相序数(Meeus)
+}
+
+impl std::fmt::Display for LunarEclipse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fmt = |x: Option<f64>| match x {
+            Some(jd) => jd2str(jd),
+            None => "--".to_string(),
+        };
+        writeln!(
+            f,
+            "{} 本影食分={:.4} 半影食分={:.4}",
+            self.kind,
+            self.umbral_magnitude,
+            self.penumbral_magnitude
+        )?;
+        writeln!(f, "半影食始 {}", jd2str(self.p1))?;
+        writeln!(f, "初亏 {}", fmt(self.u1))?;
+        writeln!(f, "食既 {}", fmt(self.u2))?;
+        writeln!(f, "食甚 {}", jd2str(self.max))?;
+        writeln!(f, "生光 {}", fmt(self.u3))?;
+        writeln!(f, "复圆 {}", fmt(self.u4))?;
+        write!(f, "半影食终 {}", jd2str(self.p4))
+    }
+}
+
+/// 月食各食相在观测地的月亮高度
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LocalLunarEclipse {
+    pub p1: EclipseContact, // 半影食始
+    pub u1: Option<EclipseContact>, // 初亏
+    pub u2: Option<EclipseContact>, // 食既
+    pub max: EclipseContact, // 食甚
+    pub u3: Option<EclipseContact>, // 生光
+    pub u4: Option<EclipseContact>, // 复圆
+    pub p4: EclipseContact, // 半影食终
+}
+
+impl std::fmt::Display for LocalLunarEclipse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "半影食始 {}", self.p1)?;
+        if let Some(c) = self.u1 {
+            writeln!(f, "初亏 {}", c)?;
+        }
+        if let Some(c) = self.u2 {
+            writeln!(f, "食既 {}", c)?;
+        }
+        writeln!(f, "食甚 {}", self.max)?;
+        if let Some(c) = self.u3 {
+            writeln!(f, "生光 {}", c)?;
+        }
+        if let Some(c) = self.u4 {
+            writeln!(f, "复圆 {}", c)?;
+        }
+        write!(f, "半影食终 {}", self.p4)
+    }
+}
+
+impl LunarEclipse {
+    /// 计算各食相时刻观测地的月亮视高度，月食地心各食相时刻全球相同
+    ///
+    /// # Arguments
+    ///
+    /// * `lon` - 经度信息， 东经为正，弧度制
+    /// * `lat` - 纬度信息，弧度制
+    pub fn local(&self, lon: f64, lat: f64) -> LocalLunarEclipse {
+        let tz = self.tz;
+        let contact = |jd: f64| {
+            let (_, _, _, _, _, _, _, _, _, _, _, _, _, alt, _) = compute_position(
+                10,
+                to_tt(jd, tz),
+                lon,
+                lat
+            );
+            EclipseContact { jd, alt }
+        };
+        LocalLunarEclipse {
+            p1: contact(self.p1),
+            u1: self.u1.map(contact),
+            u2: self.u2.map(contact),
+            max: contact(self.max),
+            u3: self.u3.map(contact),
+            u4: self.u4.map(contact),
+            p4: contact(self.p4),
+        }
+    }
+
+    /// 观测地能看到月食的时段，返回(起, 止)本地时间儒略日，看不到返回`None`
+    ///
+    /// 在半影食始到半影食终之间按10分钟步长搜索月出、月没，起止时刻取该区间内月亮第一次
+    /// 出现在地平之上和最后一次落到地平之下的时刻，极区月亮在食中出没多次时中间可能有不可见的时段
+    ///
+    /// # Example
+    /// 2022-11-8 月全食，北京月出时已在半影食中
+    /// ```
+    /// use rust_ephemeris::eclipse::*;
+    /// use rust_ephemeris::JulianDate;
+    /// let jd0 = JulianDate::from_day(2022, 11, 1.0).jd;
+    /// let e = lunar_eclipses(jd0, jd0 + 30.0, -8.0)[0];
+    /// let (lon, lat) = (116.383_f64.to_radians(), 39.9_f64.to_radians());
+    /// let (start, end) = e.visible_span(lon, lat).unwrap();
+    /// assert!(start > e.p1 && start < e.max);
+    /// assert_eq!(end, e.p4);
+    /// // 月出时刻月亮高度为0
+    /// let l = LunarEclipse { p1: start, ..e }.local(lon, lat);
+    /// assert!(l.p1.alt.abs() < 1e-4);
+    /// ```
+    pub fn visible_span(&self, lon: f64, lat: f64) -> Option<(f64, f64)> {
+        let tz = self.tz;
+        let (t0, t1) = (to_tt(self.p1, tz), to_tt(self.p4, tz));
+        let alt = |t: f64| compute_position(10, t, lon, lat).13;
+        let roots = math_utils::find_roots(alt, t0, t1, 1.0 / 144.0);
+        // 起点在地平之上，或者有月出
+        let start = if alt(t0) > 0.0 { t0 } else { roots.iter().find(|x| x.1)?.0 };
+        let end = if alt(t1) > 0.0 {
+            t1
+        } else {
+            roots
+                .iter()
+                .rev()
+                .find(|x| !x.1)?.0
+        };
+        let jd = |t: f64| if t == t0 { self.p1 } else if t == t1 { self.p4 } else { from_tt(t, tz) };
+        Some((jd(start), jd(end)))
+    }
+
+    /// 观测地是否能看到月食，即半影食始到半影食终之间月亮是否出现在地平之上
+    ///
+    /// # Example
+    /// 2001-7-5 月偏食，北纬65度、东经160度处月亮在食中升起又落下，
+    /// 半影食始、食甚、半影食终时都在地平之下
+    /// ```
+    /// use rust_ephemeris::eclipse::*;
+    /// use rust_ephemeris::JulianDate;
+    /// let jd0 = JulianDate::from_day(2001, 7, 1.0).jd;
+    /// let e = lunar_eclipses(jd0, jd0 + 10.0, 0.0)[0];
+    /// let (lon, lat) = (160_f64.to_radians(), 65_f64.to_radians());
+    /// let l = e.local(lon, lat);
+    /// assert!(l.p1.alt < 0.0 && l.max.alt < 0.0 && l.p4.alt < 0.0);
+    /// assert!(e.visible(lon, lat));
+    /// let (start, end) = e.visible_span(lon, lat).unwrap();
+    /// assert!(start > e.p1 && end < e.max);
+    /// ```
+    pub fn visible(&self, lon: f64, lat: f64) -> bool {
+        self.visible_span(lon, lat).is_some()
+    }
+}
+
+/// 计算第`k`个朔望月(Meeus月相序数)望时的月食，没有月食返回`None`
+pub fn lunar_eclipse(k: i32, tz: f64) -> Option<LunarEclipse> {
+    let (c, kind, umbral_magnitude, penumbral_magnitude) = eclipse::lunar_eclipse(k)?;
+    let jd = |t: Option<f64>| t.map(|t| from_tt(t, tz));
+    Some(LunarEclipse {
+        kind: kind.into(),
+        p1: jd(c[0])?,
+        u1: jd(c[1]),
+        u2: jd(c[2]),
+        max: jd(c[3])?,
+        u3: jd(c[4]),
+        u4: jd(c[5]),
+        p4: jd(c[6])?,
+        umbral_magnitude,
+        penumbral_magnitude,
+        tz,
+        lunation: k,
+    })
+}
+
+/// 搜索区间内的月食
+///
+/// # Arguments
+///
+/// * `jd0`, `jd1` - 起止儒略日（本地时间）或[`Instant`](crate::time::Instant)
+/// * `tz` - 时区， 比如东八区`tz=-8.0`
+///
+/// # Example
+/// 2022-11-8 月全食，北京可见
+/// ```
+/// use rust_ephemeris::eclipse::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd0 = JulianDate::from_day(2022, 11, 1.0).jd;
+/// let r = lunar_eclipses(jd0, jd0 + 30.0, -8.0);
+/// assert_eq!(r.len(), 1);
+/// assert_eq!(r[0].kind, LunarEclipseKind::Total);
+/// println!("{}", r[0]); // 食甚 2022-11-8 18h 59m 12.0s
+///
+/// let (lon, lat) = (116.383_f64.to_radians(), 39.9_f64.to_radians());
+/// println!("{}", r[0].local(lon, lat));
+/// assert!(r[0].visible(lon, lat));
+/// ```
+pub fn lunar_eclipses(jd0: impl Epoch, jd1: impl Epoch, tz: f64) -> Vec<LunarEclipse> {
+    let (jd0, jd1) = (jd0.local(tz), jd1.local(tz));
+    let k0 = lunnar::lunation_number(jd0 - constants::J2000 + (tz + 8.0) / 24.0) - 1;
+    let k1 = lunnar::lunation_number(jd1 - constants::J2000 + (tz + 8.0) / 24.0);
+    (k0..=k1)
+        .filter_map(|k| lunar_eclipse(k, tz))
+        .filter(|e| e.max >= jd0 && e.max < jd1)
+        .collect()
+}
@@ -0,0 +1,365 @@
+use std::f64::consts::PI;
+use crate::internal::{ constants, math_utils };
+use crate::internal::ephemeris::{ compute_position, parallax };
+use crate::internal::lunnar::moon_phase_accurate;
+
+pub const R_SUN: f64 = 696000.0; // 太阳半径（千米）
+pub const K_MOON: f64 = 0.2725076; // 月地半径比，半影及外切使用
+pub const K_MOON2: f64 = 0.272281; // 月地半径比，本影及内切使用（扣除月面山谷）
+
+// 月亮黄纬超过该值不会发生日食
+const ECLIPSE_LIMIT: f64 = 1.6 / 180.0 * PI;
+
+// 考虑地球扁率后，影轴与地球相交的γ极限
+const CENTRAL_LIMIT: f64 = 0.9972;
+
+// 日月地心赤道直角坐标（千米）
+fn geo_xyz(xt: usize, t: f64) -> (f64, f64, f64) {
+    let (_, _, _, _, ra, dec, _, d_e, _, _, _, _, _, _, _) = compute_position(xt, t, 0.0, 0.0);
+    let r = if xt == 10 { d_e } else { d_e * constants::CS_AU };
+    math_utils::llr2xyz((ra, dec, r))
+}
+
+/// 日食影轴几何
+///
+/// 返回(γ, 半影半径, 本影半径, 月球到基本面距离), 长度都以地球赤道半径为单位，
+/// γ为影轴到地心的距离，影轴在地心北侧为正，本影半径为负表示伪本影（环食）
+/// # Argument
+/// - `t`: 力学时，J2000起算的日数
+pub fn shadow_axis(t: f64) -> (f64, f64, f64, f64) {
+    let s = geo_xyz(9, t);
+    let m = geo_xyz(10, t);
+    let d = (m.0 - s.0, m.1 - s.1, m.2 - s.2);
+    let dd = (d.0 * d.0 + d.1 * d.1 + d.2 * d.2).sqrt();
+    let d = (d.0 / dd, d.1 / dd, d.2 / dd);
+    let q = -(m.0 * d.0 + m.1 * d.1 + m.2 * d.2); // 月球沿影轴到基本面的距离
+    let p = (m.0 + q * d.0, m.1 + q * d.1, m.2 + q * d.2); // 影轴上离地心最近的点
+    let re = constants::CS_R_EAR;
+    let gamma = (p.0 * p.0 + p.1 * p.1 + p.2 * p.2).sqrt().copysign(p.2) / re;
+    let l1 = (K_MOON * re + (q * (R_SUN + K_MOON * re)) / dd) / re;
+    let l2 = (K_MOON2 * re - (q * (R_SUN - K_MOON2 * re)) / dd) / re;
+    (gamma, l1, l2, q / re)
+}
+
+// 地面上离影轴最近的点（经度, 地理纬度），仅用于影轴不与地球相交的情况
+fn limb_point(t: f64) -> (f64, f64) {
+    let s = geo_xyz(9, t);
+    let m = geo_xyz(10, t);
+    let d = (m.0 - s.0, m.1 - s.1, m.2 - s.2);
+    let dd = (d.0 * d.0 + d.1 * d.1 + d.2 * d.2).sqrt();
+    let d = (d.0 / dd, d.1 / dd, d.2 / dd);
+    let q = -(m.0 * d.0 + m.1 * d.1 + m.2 * d.2);
+    let (ra, dec, _) = math_utils::xyz2llr((m.0 + q * d.0, m.1 + q * d.1, m.2 + q * d.2));
+    let (_, _, _, _, _, _, _, _, _, _, _, _, _, _, (_, gst)) = compute_position(9, t, 0.0, 0.0);
+    let fa = (dec.tan() / constants::CS_BA / constants::CS_BA).atan();
+    (math_utils::rad2rrad(ra - gst), fa)
+}
+
+// 黄金分割法求极小值
+pub fn golden_min<F: FnMut(f64) -> f64>(mut f: F, mut a: f64, mut b: f64, tol: f64) -> f64 {
+    let g = (5.0_f64.sqrt() - 1.0) / 2.0;
+    let mut c = b - g * (b - a);
+    let mut d = a + g * (b - a);
+    let mut fc = f(c);
+    let mut fd = f(d);
+    while (b - a).abs() > tol {
+        if fc < fd {
+            b = d;
+            d = c;
+            fd = fc;
+            c = b - g * (b - a);
+            fc = f(c);
+        } else {
+            a = c;
+            c = d;
+            fc = fd;
+            d = a + g * (b - a);
+            fd = f(d);
+        }
+    }
+    (a + b) / 2.0
+}
+
+/// 日食全局情况
+///
+/// 计算第`k`个朔望月(Meeus序数)的日食，没有日食返回`None`
+/// 返回(食甚力学时, 类型, γ, 食分, 是否中心食)，
+/// 类型 0:偏食， 1:环食， 2:全食， 3:全环食
+pub fn solar_eclipse(k: i32) -> Option<(f64, usize, f64, f64, bool)> {
+    let t = moon_phase_accurate(k, 0) - 8.0 / 24.0;
+    let t = t + math_utils::dt_t(t);
+    let (_, _, _, lat, _, _, _, _, _, _, _, _, _, _, _) = compute_position(10, t, 0.0, 0.0);
+    if lat.abs() > ECLIPSE_LIMIT {
+        return None;
+    }
+    let tm = golden_min(|x| shadow_axis(x).0.abs(), t - 0.25, t + 0.25, 1e-6);
+    let (gamma, l1, l2, q) = shadow_axis(tm);
+    let g = gamma.abs();
+    if g >= 1.0 + l1 {
+        return None;
+    }
+    let central = g < CENTRAL_LIMIT;
+    if g >= CENTRAL_LIMIT + l2.abs() {
+        // 偏食的食甚点在晨昏线上离影轴最近处，直接用站心视半径计算食分
+        let (l, fa) = limb_point(tm);
+        let (s, rs, rm, _) = local_geometry(tm, l, fa);
+        return Some((tm, 0, gamma, (rs + rm - s) / (2.0 * rs), false));
+    }
+
+    // 影轴与地面交点(非中心食取基本面)处的本影半径以及日月视直径比
+    let h = if central { (1.0 - g * g).sqrt() } else { 0.0 };
+    let qs = q - h;
+    let re = constants::CS_R_EAR;
+    let s = geo_xyz(9, tm);
+    let m = geo_xyz(10, tm);
+    let dd = ((m.0 - s.0).powi(2) + (m.1 - s.1).powi(2) + (m.2 - s.2).powi(2)).sqrt() / re;
+    let l2s = l2 + (h * (R_SUN / re - K_MOON2)) / dd;
+    let mag = (K_MOON2 / qs) / (R_SUN / re / (dd + qs));
+    let kind = match (l2 > 0.0, l2s > 0.0) {
+        (true, _) => 2,
+        (false, true) => 3,
+        _ => 1,
+    };
+    Some((tm, kind, gamma, mag, central))
+}
+
+/// 站心日月赤道坐标
+///
+/// 返回(太阳(赤经,赤纬,距离千米), 月亮(赤经,赤纬,距离千米), 太阳视高度)
+pub fn topo_sun_moon(t: f64, l: f64, fa: f64) -> ((f64, f64, f64), (f64, f64, f64), f64) {
+    let (_, _, _, _, s_ra, s_dec, _, s_r, _, _, _, _, _, alt, (_, gst)) = compute_position(
+        9,
+        t,
+        l,
+        fa
+    );
+    let (_, _, _, _, m_ra, m_dec, _, m_r, _, _, _, _, _, _, _) = compute_position(10, t, l, fa);
+    let s = parallax((s_ra, s_dec, s_r), math_utils::rad2rrad(gst + l - s_ra), fa, 0.0);
+    let m = parallax((m_ra, m_dec, m_r), math_utils::rad2rrad(gst + l - m_ra), fa, 0.0);
+    ((s.0, s.1, s.2 * constants::CS_AU), m, alt)
+}
+
+// 两个方向的夹角
+pub fn separation(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let u = math_utils::llr2xyz((a.0, a.1, 1.0));
+    let v = math_utils::llr2xyz((b.0, b.1, 1.0));
+    let c = (u.1 * v.2 - u.2 * v.1, u.2 * v.0 - u.0 * v.2, u.0 * v.1 - u.1 * v.0);
+    let sn = (c.0 * c.0 + c.1 * c.1 + c.2 * c.2).sqrt();
+    sn.atan2(u.0 * v.0 + u.1 * v.1 + u.2 * v.2)
+}
+
+/// 两圆重叠面积占第一个圆面积的比例
+///
+/// `a`、`b`是两圆半径，`c`是圆心距
+pub fn overlap(a: f64, b: f64, c: f64) -> f64 {
+    if c >= a + b {
+        return 0.0;
+    }
+    if c <= (a - b).abs() {
+        return if b >= a { 1.0 } else { (b * b) / (a * a) };
+    }
+    let s1 = a * a * ((c * c + a * a - b * b) / (2.0 * c * a)).acos();
+    let s2 = b * b * ((c * c + b * b - a * a) / (2.0 * c * b)).acos();
+    let s3 = 0.5 * ((-c + a + b) * (c + a - b) * (c - a + b) * (c + a + b)).sqrt();
+    (s1 + s2 - s3) / (PI * a * a)
+}
+
+// 站心日月角距、太阳视半径、月亮视半径（外切、内切两种）
+fn local_geometry(t: f64, l: f64, fa: f64) -> (f64, f64, f64, f64) {
+    let (s, m, _) = topo_sun_moon(t, l, fa);
+    let rs = (R_SUN / s.2).asin();
+    let rm = ((K_MOON * constants::CS_R_EAR) / m.2).asin();
+    let rm2 = ((K_MOON2 * constants::CS_R_EAR) / m.2).asin();
+    (separation((s.0, s.1), (m.0, m.1)), rs, rm, rm2)
+}
+
+/// 日食地方情况
+///
+/// 在全局食甚`tm`前后计算观测地的初亏、食既、食甚、生光、复圆，观测地看不到日食返回`None`
+/// 返回([初亏, 食既, 食甚, 生光, 复圆], 食分, 食面积比, 类型) 类型 0:偏食， 1:环食， 2:全食
+pub fn local_solar_eclipse(tm: f64, l: f64, fa: f64) -> Option<([Option<f64>; 5], f64, f64, usize)> {
+    let (t0, t1, step) = (tm - 0.2, tm + 0.2, 10.0 / 1440.0);
+    let outer = math_utils::find_roots(
+        |t| {
+            let (s, rs, rm, _) = local_geometry(t, l, fa);
+            s - rs - rm
+        },
+        t0,
+        t1,
+        step
+    );
+    let c1 = outer.iter().find(|x| !x.1)?.0;
+    let c4 = outer.iter().find(|x| x.1 && x.0 > c1)?.0;
+
+    // 食甚，角距的极小值
+    let t = golden_min(|t| local_geometry(t, l, fa).0, c1, c4, 1e-7);
+    let (s, rs, rm, rm2) = local_geometry(t, l, fa);
+    let mag = (rs + rm - s) / (2.0 * rs);
+    let obscuration = overlap(rs, rm, s);
+
+    let mut c = [Some(c1), None, Some(t), None, Some(c4)];
+    let mut kind = 0;
+    if s < (rm2 - rs).abs() {
+        kind = if rm2 > rs { 2 } else { 1 };
+        // 全食或环食持续时间可能只有几秒，直接在初亏-食甚、食甚-复圆之间求根
+        let f = |t: f64| {
+            let (s, rs, _, rm2) = local_geometry(t, l, fa);
+            s - (rm2 - rs).abs()
+        };
+        c[1] = math_utils::find_roots(f, c1, t, t - c1).first().map(|x| x.0);
+        c[3] = math_utils::find_roots(f, t, c4, c4 - t).first().map(|x| x.0);
+    }
+    Some((c, mag, obscuration, kind))
+}
+
+// 月食地影(Danjon法)：地球半径增大1/85以考虑大气
+const SHADOW_ENLARGE: f64 = 1.0 + 1.0 / 85.0;
+
+/// 月食几何（地心）
+///
+/// 返回(月亮与地影中心角距, 本影半径, 半影半径, 月亮视半径)，弧度制
+/// # Argument
+/// - `t`: 力学时，J2000起算的日数
+pub fn lunar_geometry(t: f64) -> (f64, f64, f64, f64) {
+    let (_, _, _, _, s_ra, s_dec, _, s_r, _, _, _, _, _, _, _) = compute_position(9, t, 0.0, 0.0);
+    let (_, _, _, _, m_ra, m_dec, _, m_r, _, _, _, _, _, _, _) = compute_position(10, t, 0.0, 0.0);
+    let pi_m = (constants::CS_R_EAR / m_r).asin(); // 月亮地平视差
+    let pi_s = constants::CS_R_EAR / (s_r * constants::CS_AU); // 太阳地平视差
+    let ss = R_SUN / (s_r * constants::CS_AU); // 太阳视半径
+    let sm = (K_MOON * constants::CS_R_EAR / m_r).asin();
+    let pi1 = SHADOW_ENLARGE * 0.99834 * pi_m; // 取地球平均半径
+    let umbra = pi1 + pi_s - ss;
+    let penumbra = pi1 + pi_s + ss;
+    let sep = separation((s_ra + PI, -s_dec), (m_ra, m_dec));
+    (sep, umbra, penumbra, sm)
+}
+
+/// 月食
+///
+/// 计算第`k`个朔望月(Meeus序数)望时的月食，没有月食返回`None`
+/// 返回([半影食始, 初亏, 食既, 食甚, 生光, 复圆, 半影食终], 类型, 本影食分, 半影食分)，时间是力学时，
+/// 类型 0:半影月食， 1:月偏食， 2:月全食
+pub fn lunar_eclipse(k: i32) -> Option<([Option<f64>; 7], usize, f64, f64)> {
+    let t = moon_phase_accurate(k, 2) - 8.0 / 24.0;
+    let t = t + math_utils::dt_t(t);
+    let (_, _, _, lat, _, _, _, _, _, _, _, _, _, _, _) = compute_position(10, t, 0.0, 0.0);
+    if lat.abs() > ECLIPSE_LIMIT {
+        return None;
+    }
+    let tm = golden_min(|x| lunar_geometry(x).0, t - 0.25, t + 0.25, 1e-7);
+    let (sep, umbra, penumbra, sm) = lunar_geometry(tm);
+    let umag = (umbra + sm - sep) / (2.0 * sm);
+    let pmag = (penumbra + sm - sep) / (2.0 * sm);
+    if pmag <= 0.0 {
+        return None;
+    }
+
+    // 角距在食甚前单调减小，食甚后单调增大，半影月食最长不超过6小时
+    let contact = |g: fn(f64, f64, f64) -> f64| {
+        let f = |x: f64| {
+            let (sep, umbra, penumbra, sm) = lunar_geometry(x);
+            sep - g(umbra, penumbra, sm)
+        };
+        let a = math_utils::find_roots(f, tm - 0.3, tm, 0.3).first().map(|x| x.0);
+        let b = math_utils::find_roots(f, tm, tm + 0.3, 0.3).first().map(|x| x.0);
+        (a, b)
+    };
+    let (p1, p4) = contact(|_, p, sm| p + sm);
+    let (u1, u4) = contact(|u, _, sm| u + sm);
+    let (u2, u3) = contact(|u, _, sm| u - sm);
+    let kind = if umag >= 1.0 {
+        2
+    } else if umag > 0.0 {
+        1
+    } else {
+        0
+    };
+    Some(([p1, u1, u2, Some(tm), u3, u4, p4], kind, umag, pmag))
+}
+
+#[test]
+fn test_solar_eclipse() {
+    // NASA 2024-04-08 全食 食甚18:17:16 TD γ=0.3431 食分1.0566
+    let k = 300;
+    let (t, kind, gamma, mag, central) = solar_eclipse(k).unwrap();
+    assert!((t - 8864.2620).abs() < 2.0 / 1440.0);
+    assert_eq!(kind, 2);
+    assert!((gamma - 0.3431).abs() < 0.002 && (mag - 1.0566).abs() < 0.002 && central);
+
+    // 2023-10-14 环食 γ=0.3753 食分0.9520
+    let (_, kind, gamma, mag, _) = solar_eclipse(k - 6).unwrap();
+    assert_eq!(kind, 1);
+    assert!((gamma - 0.3753).abs() < 0.002 && (mag - 0.952).abs() < 0.002);
+
+    // 2023-04-20 全环食 γ=-0.3952 食分1.0132
+    let (_, kind, gamma, mag, _) = solar_eclipse(k - 12).unwrap();
+    assert_eq!(kind, 3);
+    assert!((gamma + 0.3952).abs() < 0.002 && (mag - 1.0132).abs() < 0.002);
+
+    // 2022-10-25 偏食 γ=1.0701 食分0.8619
+    let (_, kind, gamma, mag, central) = solar_eclipse(k - 18).unwrap();
+    assert_eq!(kind, 0);
+    assert!((gamma - 1.0701).abs() < 0.002 && (mag - 0.8619).abs() < 0.002 && !central);
+    assert!(solar_eclipse(k - 1).is_none());
+}
+
+#[test]
+fn test_local_solar_eclipse() {
+    // 2024-04-08 达拉斯 全食 初亏17:23:20 食既18:40:43 生光18:44:35 复圆20:02:48 (UT)
+    let l = -96.797_f64.to_radians();
+    let fa = 32.777_f64.to_radians();
+    let (t, _, _, _, _) = solar_eclipse(300).unwrap();
+    let (c, mag, obs, kind) = local_solar_eclipse(t, l, fa).unwrap();
+    let ut = |x: Option<f64>| {
+        let x = x.unwrap();
+        (x - math_utils::dt_t(x) - 8863.5) * 24.0
+    };
+    let exp_ = [17.0 + 23.0 / 60.0 + 20.0 / 3600.0, 18.0 + 40.0 / 60.0 + 43.0 / 3600.0, 0.0, 18.0 + 44.0 / 60.0 + 35.0 / 3600.0, 20.0 + 2.0 / 60.0 + 48.0 / 3600.0];
+    for i in [0, 1, 3, 4] {
+        assert!((ut(c[i]) - exp_[i]).abs() < 0.5 / 60.0);
+    }
+    assert_eq!(kind, 2);
+    assert!(mag > 1.0 && (obs - 1.0).abs() < 1e-9);
+
+    // 北京位于半影区内，但太阳在地平之下
+    let (l, fa) = (116.383_f64.to_radians(), 39.9_f64.to_radians());
+    let (c, _, _, kind) = local_solar_eclipse(t, l, fa).unwrap();
+    assert_eq!(kind, 0);
+    assert!(topo_sun_moon(c[2].unwrap(), l, fa).2 < 0.0);
+    // 利马在半影之外
+    assert!(local_solar_eclipse(t, -77.04_f64.to_radians(), -12.05_f64.to_radians()).is_none());
+}
+
+#[test]
+fn test_lunar_eclipse() {
+    // NASA 2022-11-08 月全食 本影食分1.3589 半影食分2.4143
+    // 半影食始08:02:17 初亏09:09:12 食既10:16:39 食甚10:59:11 生光11:41:48 复圆12:49:10 半影食终13:56:09 (UT)
+    let (c, kind, umag, pmag) = lunar_eclipse(282).unwrap();
+    let ut = |x: f64| (x - math_utils::dt_t(x) - 8346.5) * 24.0;
+    let hms = |h: f64, m: f64, s: f64| h + m / 60.0 + s / 3600.0;
+    let exp_ = [
+        hms(8.0, 2.0, 17.0),
+        hms(9.0, 9.0, 12.0),
+        hms(10.0, 16.0, 39.0),
+        hms(10.0, 59.0, 11.0),
+        hms(11.0, 41.0, 48.0),
+        hms(12.0, 49.0, 10.0),
+        hms(13.0, 56.0, 9.0),
+    ];
+    for i in 0..7 {
+        assert!((ut(c[i].unwrap()) - exp_[i]).abs() < 1.0 / 60.0);
+    }
+    assert_eq!(kind, 2);
+    assert!((umag - 1.3589).abs() < 0.005 && (pmag - 2.4143).abs() < 0.005);
+
+    // 2023-10-28 月偏食 本影食分0.1224
+    let (c, kind, umag, _) = lunar_eclipse(294).unwrap();
+    assert_eq!(kind, 1);
+    assert!((umag - 0.1224).abs() < 0.005 && c[2].is_none());
+
+    // 2023-05-05 半影月食 半影食分0.9630
+    let (c, kind, _, pmag) = lunar_eclipse(288).unwrap();
+    assert_eq!(kind, 0);
+    assert!((pmag - 0.963).abs() < 0.005 && c[1].is_none());
+    assert!(lunar_eclipse(289).is_none());
+}
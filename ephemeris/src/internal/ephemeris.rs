@@ -1,5 +1,6 @@
-use crate::internal::{ constants, math_utils::{ self, llr_conv, xyz2llr } };
+use crate::internal::{ constants, siyu, math_utils::{ self, llr_conv, xyz2llr } };
 use std::f64::consts::PI;
+use std::cell::Cell;
 
 // 章动相关计算
 fn nutation2(t: f64) -> (f64, f64) {
@@ -20,7 +21,7 @@ fn nutation2(t: f64) -> (f64, f64) {
     (dl / 100.0 / constants::RAD, de / 100.0 / constants::RAD)
 }
 
-fn nutation_lon2(w: f64) -> f64 {
+pub fn nutation_lon2(w: f64) -> f64 {
     let (x, _) = nutation2(w);
     x
 }
@@ -388,7 +389,7 @@ fn mqc(h: f64) -> f64 {
 }
 
 // 大气折射（ho 是视高度）
-fn mqc2(ho: f64) -> f64 {
+pub fn mqc2(ho: f64) -> f64 {
     -0.0002909 / (ho + 0.002227 / (ho + 0.07679)).tan()
 }
 
@@ -472,6 +473,14 @@ pub fn pgst2(jd: f64) -> f64 {
     pgst(jd - dt, dt)
 }
 
+// 传入力学时J2000起算日数，返回(格林尼治真恒星时, 真黄赤交角)，与天体坐标计算使用相同的章动
+pub fn true_sidereal(jd: f64) -> (f64, f64) {
+    let t = jd / 36525.0;
+    let (d_l, d_e) = nutation2(t);
+    let e = obliquity(t) + d_e;
+    (pgst2(jd) + d_l * e.cos(), e)
+}
+
 // 天体坐标计算
 
 #[derive(Debug, Clone, Copy)]
@@ -539,6 +548,22 @@ pub fn hdllr_j2d(t: f64, llr: (f64, f64, f64), model: PlutoModel) -> (f64, f64,
     llr_conv((r0, r1, r2), -prece(t, PlutoParam::PE, model))
 }
 
+// 日期黄道坐标转为J2000黄道坐标，hdllr_j2d的逆变换
+pub fn hdllr_d2j(t: f64, llr: (f64, f64, f64), model: PlutoModel) -> (f64, f64, f64) {
+    let (mut r0, r1, r2) = llr_conv(llr, prece(t, PlutoParam::PE, model));
+    r0 += prece(t, PlutoParam::Px, model);
+    let (r0, r1, r2) = llr_conv((r0, r1, r2), -prece(t, PlutoParam::Pw, model));
+    (math_utils::rad2mrad(r0 - prece(t, PlutoParam::Pfi, model)), r1, r2)
+}
+
+#[test]
+fn test_hdllr_d2j() {
+    let t = 0.23;
+    let z = (1.3, 0.02, 5.2);
+    let r = hdllr_d2j(t, hdllr_j2d(t, z, PlutoModel::P03), PlutoModel::P03);
+    assert!((r.0 - z.0).abs() < 1e-12 && (r.1 - z.1).abs() < 1e-12);
+}
+
 // 冥王行坐标计算
 fn pluto_coord(t: f64) -> (f64, f64, f64) {
     let c0 = PI / 180.0 / 100000.0;
@@ -615,9 +640,67 @@ fn test_p_coord1() {
 }
 
 
-// xt星体， jd 儒略日（相对于J2000天数），l经度， fa:纬度
-pub fn compute_position(xt: usize, jd: f64, l: f64, fa: f64) -> 
- (f64, f64, f64, f64, f64, f64, f64, f64, f64, f64, f64, f64, f64, f64, (f64, f64)) {
+thread_local! {
+    // 当前线程的级数截断级别，0为全部项，见terms
+    static PRECISION: Cell<usize> = const { Cell::new(0) };
+}
+
+/// 在`f`执行期间使用级数截断级别`level`，只对当前线程有效，返回(包括panic)后恢复原级别
+///
+/// 0:全部项， 1:高， 2:中， 3:低，其余按0处理
+pub fn with_precision<R>(level: usize, f: impl FnOnce() -> R) -> R {
+    struct Restore(usize);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            PRECISION.with(|p| p.set(self.0));
+        }
+    }
+    let _restore = Restore(PRECISION.with(|p| p.replace(level)));
+    f()
+}
+
+/// 当前线程的级数截断级别
+pub fn precision() -> usize {
+    PRECISION.with(|p| p.get())
+}
+
+// 当前级别下(行星, 月亮)级数的项数参数，-1为全部项
+// 1900-2100年与全部项相比的最大误差(视黄经、视黄纬)，见test_precision_error：
+// 高: 行星1角秒，月亮2.5角秒；中: 行星15角秒，月亮9角秒；低: 行星55角秒，月亮30角秒
+fn terms() -> (i32, i32) {
+    match precision() {
+        1 => (160, 160),
+        2 => (40, 80),
+        3 => (20, 40),
+        _ => (-1, -1),
+    }
+}
+
+/// 观测点
+///
+/// 海拔、大气折射比例以及地平俯角，用于站心坐标、地平坐标以及出没计算
+#[derive(Debug, Clone, Copy)]
+pub struct Site {
+    pub high: f64, // 海拔（千米）
+    pub refr: f64, // 大气折射相对标准大气(1010百帕、10摄氏度)的比例
+    pub dip: f64, // 地平俯角（弧度）
+}
+
+/// 海平面、标准大气
+pub const SEA_LEVEL: Site = Site { high: 0.0, refr: 1.0, dip: 0.0 };
+
+/// 天体位置计算结果
+///
+/// (黄经, 黄纬, 视黄经, 视黄纬, 视赤经, 视赤纬, 向径, 地心距, 光行距, 站赤经, 站赤纬, 视距离, 方位角, 高度角, (平恒星时, 真恒星时))
+pub type Position = (f64, f64, f64, f64, f64, f64, f64, f64, f64, f64, f64, f64, f64, f64, (f64, f64));
+
+// xt星体(11-16为四余虚点，见siyu::siyu_coord)， jd 儒略日（相对于J2000天数），l经度， fa:纬度
+pub fn compute_position(xt: usize, jd: f64, l: f64, fa: f64) -> Position {
+    compute_position_at(xt, jd, l, fa, &SEA_LEVEL)
+}
+
+// 同compute_position，站心坐标和地平坐标按观测点site计算
+pub fn compute_position_at(xt: usize, jd: f64, l: f64, fa: f64, site: &Site) -> Position {
     let  mut eclon_: f64 = 0.0; // 黄经
     let  mut eclat_: f64 = 0.0; // 黄纬
 
@@ -639,6 +722,7 @@ pub fn compute_position(xt: usize, jd: f64, l: f64, fa: f64) ->
     //行星计算，jd力学时
     //基本参数计算
     let mut t = jd / 36525.0;
+    let (n, nm) = terms();
     let zd = nutation2(t);
     let (d_l, d_e) = zd; //章动
     let e = obliquity(t) + d_e; //真黄赤交角
@@ -653,6 +737,20 @@ pub fn compute_position(xt: usize, jd: f64, l: f64, fa: f64) ->
     let mut rb:f64;
     let mut rc=0.0;
 
+    if xt > 10 {
+        //四余虚点，没有光行时和视差
+        z = siyu::siyu_coord(xt, t);
+        eclon_ = z.0;
+        eclat_ = z.1;
+        rc = z.2;
+        z.0 = math_utils::rad2mrad(z.0 + d_l); //补章动
+        a_lon_ = z.0;
+        a_lat_ = z.1;
+        d_e_ = z.2;
+        z = llr_conv(z, e);
+        a_ra_ = z.0;
+        a_dec_ = z.1;
+    }
     if xt == 10 {
         //月亮
         //求光行时并精确求出地月距
@@ -663,9 +761,15 @@ pub fn compute_position(xt: usize, jd: f64, l: f64, fa: f64) ->
         // let cs_agx = constants::CS_AU / constants::CS_GS / 86400.0 / 36525.0;
         t -= (ra * constants::CS_AGX) / constants::CS_AU; //光行时计算
 
+        //日心坐标
+        let h = moon_helio(jd / 36525.0);
+        eclon_ = h.0;
+        eclat_ = h.1;
+        r_ = h.2;
+
         //求视坐标
         a2 = e_coord(t, 15, 15, 15); //地球
-        z = m_coord(t, -1, -1, -1);
+        z = m_coord(t, nm, nm, nm);
         rc = z.2; //月亮
 
         //求光行距
@@ -687,8 +791,8 @@ pub fn compute_position(xt: usize, jd: f64, l: f64, fa: f64) ->
     }
     if xt < 10 {
         //行星和太阳
-        a = p_coord(0, t, -1, -1, -1); //地球
-        z = p_coord(xt, t, -1, -1, -1); //行星
+        a = p_coord(0, t, n, n, n); //地球
+        z = p_coord(xt, t, n, n, n); //行星
         z.0 = math_utils::rad2mrad(z.0);
         eclon_=z.0;
         eclat_=z.1;
@@ -701,8 +805,8 @@ pub fn compute_position(xt: usize, jd: f64, l: f64, fa: f64) ->
         t -= ra * constants::CS_AGX; // cs_agx; //光行时
 
         //重算坐标
-        a2 = p_coord(0, t, -1, -1, -1); //地球
-        z2 = p_coord(xt, t, -1, -1, -1); //行星
+        a2 = p_coord(0, t, n, n, n); //地球
+        z2 = p_coord(xt, t, n, n, n); //行星
         z = h2g(z2, a);
         rb = z.2; //rb光行距（在惯性系中看）
         z = h2g(z2, a2);
@@ -720,7 +824,9 @@ pub fn compute_position(xt: usize, jd: f64, l: f64, fa: f64) ->
 
     let sj = math_utils::rad2rrad(gst + l - z.0); //得到天体时角
 
-    z = parallax(z, sj, fa, 0.0); //视差修正
+    if xt <= 10 {
+        z = parallax(z, sj, fa, site.high); //视差修正
+    }
     st_ra_ = z.0;
     st_dec_ = z.1;
     dist_ = rc;
@@ -729,15 +835,15 @@ pub fn compute_position(xt: usize, jd: f64, l: f64, fa: f64) ->
     z = llr_conv(z, PI / 2.0 - fa); //转到时角坐标转到地平坐标
     z.0 = math_utils::rad2mrad(PI / 2.0 - z.0);
 
-    if z.1 > 0.0 {
-        z.1 += mqc(z.1); //大气折射修正
+    if z.1 > -site.dip {
+        z.1 += mqc(z.1) * site.refr; //大气折射修正
     }
     az_ = z.0;
     alt_ = z.1;
     sid_time_ =(gst_ping, gst);
    
 
-    let pos: (f64, f64, f64, f64, f64, f64, f64, f64, f64, f64, f64, f64, f64, f64, (f64, f64)) =(eclon_,eclat_,
+    let pos: Position =(eclon_,eclat_,
         a_lon_,a_lat_,a_ra_,
         a_dec_,
         r_,
@@ -754,6 +860,252 @@ pub fn compute_position(xt: usize, jd: f64, l: f64, fa: f64) ->
     pos
 }
 
+// 月亮日心黄道坐标(天文单位)，t为J2000起算的儒略世纪数
+fn moon_helio(t: f64) -> (f64, f64, f64) {
+    let (n, nm) = terms();
+    let e = math_utils::llr2xyz(e_coord(t, n, n, n));
+    let mut m = m_coord(t, nm, nm, nm);
+    m.2 /= constants::CS_AU;
+    let m = math_utils::llr2xyz(m);
+    xyz2llr((e.0 + m.0, e.1 + m.1, e.2 + m.2))
+}
+
+// 地心坐标，level: 0:几何， 1:加光行时， 2:再加光行差
+fn geocentric(xt: usize, t: f64, level: usize) -> (f64, f64, f64) {
+    if xt > 10 {
+        let mut z = siyu::siyu_coord(xt, t);
+        z.2 /= constants::CS_AU;
+        return z;
+    }
+    let (n, nm) = terms();
+    if xt == 10 {
+        // 月亮的光行差与光行时的影响合在一起，只需按光行时回推
+        let mut z = m_coord(t, nm, nm, nm);
+        if level > 0 {
+            z = m_coord(t - z.2 * constants::CS_AGX / constants::CS_AU, nm, nm, nm);
+        }
+        z.2 /= constants::CS_AU;
+        return z;
+    }
+    let a = p_coord(0, t, n, n, n);
+    let z = h2g(p_coord(xt, t, n, n, n), a);
+    if level == 0 {
+        return z;
+    }
+    let t2 = t - z.2 * constants::CS_AGX;
+    let z2 = p_coord(xt, t2, n, n, n);
+    // 地球也取光行时之前的位置，即计入了周年光行差
+    let a = if level == 1 { a } else { p_coord(0, t2, n, n, n) };
+    h2g(z2, a)
+}
+
+/// 指定中心和改正级别的坐标
+///
+/// 返回(黄道坐标(黄经, 黄纬, 距离), 赤道坐标(赤经, 赤纬))，距离统一为天文单位。
+/// 视位置为真春分点，其余为平春分点，黄道坐标都是瞬时黄道
+/// # Argument
+/// - `xt`: 星体序号，日心只支持0-10
+/// - `jd`: 力学时，J2000起算的日数
+/// - `origin`: 0:日心， 1:地心， 2:站心
+/// - `level`: 0:几何位置， 1:天体测量位置(加光行时)， 2:再加光行差， 3:视位置(再加章动)。
+///   日心坐标不计光行时和光行差，月亮的光行差已含在光行时中
+/// - `l`, `fa`, `high`: 站心坐标的经度、纬度和海拔(千米)
+pub fn reduced_position(
+    xt: usize,
+    jd: f64,
+    origin: usize,
+    level: usize,
+    l: f64,
+    fa: f64,
+    high: f64
+) -> ((f64, f64, f64), (f64, f64)) {
+    let t = jd / 36525.0;
+    let mut z = match origin {
+        0 => {
+            assert!(xt <= 10, "heliocentric position is not defined for siyu");
+            match xt {
+                9 => (0.0, 0.0, 0.0),
+                10 => moon_helio(t),
+                _ => {
+                    let n = terms().0;
+                    p_coord(xt, t, n, n, n)
+                }
+            }
+        }
+        _ => geocentric(xt, t, level),
+    };
+    let (d_l, d_e) = nutation2(t);
+    let mut e = obliquity(t);
+    if level >= 3 {
+        z.0 += d_l;
+        e += d_e;
+    }
+    z.0 = math_utils::rad2mrad(z.0);
+    let mut q = llr_conv(z, e);
+    if origin == 2 && xt <= 10 {
+        let gst = pgst2(jd) + d_l * e.cos();
+        q = parallax(q, math_utils::rad2rrad(gst + l - q.0), fa, high);
+        z = llr_conv(q, -e);
+        z.2 = q.2;
+    }
+    (z, (q.0, q.1))
+}
+
+// 行星质量(太阳质量为1)，按星体序号排列，地球含月球
+const PLANET_MASS: [f64; 9] = [
+    1.0 / 328900.56,
+    1.0 / 6023600.0,
+    1.0 / 408523.71,
+    1.0 / 3098708.0,
+    1.0 / 1047.3486,
+    1.0 / 3497.898,
+    1.0 / 22902.98,
+    1.0 / 19412.24,
+    1.0 / 135200000.0,
+];
+
+// J2000平黄赤交角
+const E2000: f64 = 84381.406 / constants::RAD;
+
+// J2000黄道日心直角坐标(天文单位)，t为J2000起算的儒略世纪数
+fn helio_j2000(xt: usize, t: f64) -> (f64, f64, f64) {
+    let z = match xt {
+        9 => return (0.0, 0.0, 0.0),
+        10 => moon_helio(t),
+        _ => {
+            let n = terms().0;
+            p_coord(xt, t, n, n, n)
+        }
+    };
+    math_utils::llr2xyz(hdllr_d2j(t, z, PlutoModel::P03))
+}
+
+// 太阳相对太阳系质心的位置，由各行星位置按质量加权得到
+fn sun_barycentric(t: f64) -> (f64, f64, f64) {
+    let mut r = (0.0, 0.0, 0.0);
+    let mut m = 1.0;
+    for (xt, k) in PLANET_MASS.iter().enumerate() {
+        let p = helio_j2000(xt, t);
+        r = (r.0 - k * p.0, r.1 - k * p.1, r.2 - k * p.2);
+        m += k;
+    }
+    (r.0 / m, r.1 / m, r.2 / m)
+}
+
+/// J2000直角坐标的位置和速度
+///
+/// 返回((x, y, z), (vx, vy, vz))，单位为天文单位和天文单位/日，速度由位置中心差分得到。
+/// 质心坐标中太阳的位置由行星质量加权得到，精度约千分之一
+/// # Argument
+/// - `xt`: 星体序号，0-10
+/// - `jd`: 力学时，J2000起算的日数
+/// - `center`: 0:日心， 1:太阳系质心
+/// - `frame`: 0:J2000黄道， 1:J2000赤道
+pub fn state_vector(xt: usize, jd: f64, center: usize, frame: usize) -> ((f64, f64, f64), (f64, f64, f64)) {
+    assert!(xt <= 10, "state vector is not defined for siyu");
+    let pos = |d: f64| {
+        let t = d / 36525.0;
+        let mut p = helio_j2000(xt, t);
+        if center == 1 {
+            let s = sun_barycentric(t);
+            p = (p.0 + s.0, p.1 + s.1, p.2 + s.2);
+        }
+        if frame == 1 {
+            let (c, s) = (E2000.cos(), E2000.sin());
+            p = (p.0, p.1 * c - p.2 * s, p.1 * s + p.2 * c);
+        }
+        p
+    };
+    let h = 0.01;
+    let (a, b) = (pos(jd - h), pos(jd + h));
+    let v = ((b.0 - a.0) / (2.0 * h), (b.1 - a.1) / (2.0 * h), (b.2 - a.2) / (2.0 * h));
+    (pos(jd), v)
+}
+
+/// 地心天体测量位置
+///
+/// J2000赤道直角坐标(天文单位)，计光行时，不计光行差和章动，返回(位置, 光行时(日))
+/// # Argument
+/// - `xt`: 星体序号，0-9
+/// - `jd`: 力学时，J2000起算的日数
+pub fn astrometric_j2000(xt: usize, jd: f64) -> ((f64, f64, f64), f64) {
+    let e = helio_j2000(0, jd / 36525.0);
+    let mut tau = 0.0;
+    let mut d = (0.0, 0.0, 0.0);
+    for _ in 0..3 {
+        let p = helio_j2000(xt, (jd - tau) / 36525.0);
+        d = (p.0 - e.0, p.1 - e.1, p.2 - e.2);
+        tau = (d.0 * d.0 + d.1 * d.1 + d.2 * d.2).sqrt() * constants::CS_AGX * 36525.0;
+    }
+    let (c, s) = (E2000.cos(), E2000.sin());
+    ((d.0, d.1 * c - d.2 * s, d.1 * s + d.2 * c), tau)
+}
+
+/// 视黄经、视黄纬、地心距的日变化
+///
+/// 对`compute_position`的视坐标中心差分求导，返回(黄经速度, 黄纬速度, 距离速度)，
+/// 角度单位是弧度/日，距离单位与地心距相同(月亮千米，其余天文单位)，黄经速度为负表示逆行。
+/// 地球没有地心坐标，返回日心黄经、黄纬、向径的变化
+/// # Argument
+/// - `xt`: 星体序号
+/// - `jd`: 力学时，J2000起算的日数
+pub fn compute_speed(xt: usize, jd: f64) -> (f64, f64, f64) {
+    let h = 0.01;
+    let coord = |t: f64| {
+        let (eclon, eclat, a_lon, a_lat, _, _, r, d_e, _, _, _, _, _, _, _) = compute_position(
+            xt,
+            t,
+            0.0,
+            0.0
+        );
+        if xt == 0 { (eclon, eclat, r) } else { (a_lon, a_lat, d_e) }
+    };
+    let (lon0, lat0, d0) = coord(jd - h);
+    let (lon1, lat1, d1) = coord(jd + h);
+    (
+        math_utils::rad2rrad(lon1 - lon0) / (2.0 * h),
+        (lat1 - lat0) / (2.0 * h),
+        (d1 - d0) / (2.0 * h),
+    )
+}
+
+#[test]
+fn test_compute_speed() {
+    // 月亮每日12-15度
+    let (v, _, _) = compute_speed(10, 8600.0);
+    assert!(v.to_degrees() > 11.5 && v.to_degrees() < 15.5);
+    // 7月太阳每日约0.955度
+    let (v, _, _) = compute_speed(9, 8600.0);
+    assert!((v.to_degrees() - 0.955).abs() < 0.01);
+    // 2023-05-01 水星逆行
+    let (v, _, _) = compute_speed(1, 8520.5);
+    assert!(v < 0.0);
+}
+
+/// 时差(真太阳时减平太阳时)
+///
+/// 按Meeus公式28.3计算，太阳视赤经取`compute_position`的结果，返回弧度，正值表示日晷快于平太阳时
+/// # Argument
+/// - `jd`: 力学时，J2000起算的日数
+pub fn equation_of_time(jd: f64) -> f64 {
+    let t = jd / 36525.0;
+    let tau = t / 10.0;
+    let l0 = 280.4664567 + 360007.6982779 * tau + 0.03032028 * tau.powi(2) + tau.powi(3) / 49931.0 -
+        tau.powi(4) / 15300.0 - tau.powi(5) / 2000000.0; // 太阳平黄经(度)
+    let (d_l, d_e) = nutation2(t);
+    let e = obliquity(t) + d_e;
+    let (_, _, _, _, ra, _, _, _, _, _, _, _, _, _, _) = compute_position(9, jd, 0.0, 0.0);
+    math_utils::rad2rrad((l0 - 0.0057183).to_radians() - ra + d_l * e.cos())
+}
+
+#[test]
+fn test_equation_of_time() {
+    // Meeus 例28.b 1992-10-13 0h TD 时差13m42.7s
+    let e = equation_of_time(2448908.5 - 2451545.0);
+    println!("{}", (e * 1440.0) / (2.0 * PI));
+    assert!(((e * 1440.0) / (2.0 * PI) - 13.7094).abs() < 0.01);
+}
+
 #[test]
 fn test_xing_x() {
     let xt = 8_usize;
@@ -771,3 +1123,78 @@ fn test_xing_monn() {
     let fa = -0.38746309394274114;
  compute_position(xt, jd, l, fa);
 }
+
+#[test]
+fn test_reduced_position() {
+    // 视位置与compute_position一致
+    let t = 8603.5;
+    for xt in [3, 9, 10] {
+        let p = compute_position(xt, t, 2.0, 0.6);
+        let (z, q) = reduced_position(xt, t, 1, 3, 0.0, 0.0, 0.0);
+        assert!(math_utils::rad2rrad(z.0 - p.2).abs() < 1e-7, "{}", xt);
+        assert!((q.1 - p.5).abs() < 1e-7);
+        let (_, q) = reduced_position(xt, t, 2, 3, 2.0, 0.6, 0.0);
+        assert!(math_utils::rad2rrad(q.0 - p.9).abs() < 1e-7);
+        assert!((q.1 - p.10).abs() < 1e-7);
+    }
+
+    // Meeus 例33.a 金星 1992-12-20 0h TD 视赤经21h04m41.454s 视赤纬-18°53'16.84"
+    let (_, q) = reduced_position(2, -2568.5, 1, 3, 0.0, 0.0, 0.0);
+    assert!((q.0.to_degrees() / 15.0 - (21.0 + 4.0 / 60.0 + 41.454 / 3600.0)).abs() * 54000.0 < 1.0);
+    assert!((q.1.to_degrees() + (18.0 + 53.0 / 60.0 + 16.84 / 3600.0)).abs() * 3600.0 < 2.0);
+
+    // 光行时使金星位置相差几十角秒，几何位置与日心坐标由地球位置联系
+    let (g, _) = reduced_position(2, -2568.5, 1, 0, 0.0, 0.0, 0.0);
+    let (a, _) = reduced_position(2, -2568.5, 1, 1, 0.0, 0.0, 0.0);
+    let d = math_utils::rad2rrad(a.0 - g.0).abs() * constants::RAD;
+    assert!(d > 5.0 && d < 200.0);
+    let (h, _) = reduced_position(2, -2568.5, 0, 0, 0.0, 0.0, 0.0);
+    let (e, _) = reduced_position(0, -2568.5, 0, 0, 0.0, 0.0, 0.0);
+    let x = h2g(h, e);
+    assert!(math_utils::rad2rrad(x.0 - g.0).abs() < 1e-12);
+
+    // 月亮日心坐标
+    let p = compute_position(10, t, 0.0, 0.0);
+    assert!((p.6 - 1.0).abs() < 0.03);
+}
+
+#[test]
+fn test_state_vector() {
+    // J2000.0 地球日心坐标 x=-0.17714 y=0.96724 vx=-0.017200 vy=-0.003158 (DE405)
+    let (p, v) = state_vector(0, 0.0, 0, 0);
+    assert!((p.0 + 0.17714).abs() < 1e-4 && (p.1 - 0.96724).abs() < 1e-4 && p.2.abs() < 1e-5);
+    assert!((v.0 + 0.017200).abs() < 1e-5 && (v.1 + 0.003158).abs() < 1e-5);
+    let (q, _) = state_vector(0, 0.0, 0, 1);
+    assert!((q.1 - 0.88744).abs() < 1e-4 && (q.2 - 0.38474).abs() < 1e-4);
+
+    // J2000.0 太阳质心坐标 x=-0.007137 y=-0.002796 z=0.000206 (DE405)
+    let (s, _) = state_vector(9, 0.0, 1, 0);
+    assert!((s.0 + 0.007137).abs() < 2e-5 && (s.1 + 0.002796).abs() < 2e-5 && (s.2 - 0.000206).abs() < 2e-5);
+}
+
+
+#[test]
+fn test_precision_error() {
+    // 1900-2100年各级别与全部项相比的最大误差(视黄经、视黄纬，角秒)：(级别, 行星, 月亮)
+    // 与astronomy::Precision文档中的表一致
+    let bounds = [(1, 1.0, 2.5), (2, 15.0, 9.0), (3, 55.0, 30.0)];
+    let arcsec = 180.0 * 3600.0 / PI;
+    for (level, planet, moon) in bounds {
+        let mut max = (0.0_f64, 0.0_f64);
+        for i in 0..400 {
+            let t = -36525.0 + (i as f64) * 182.6 + ((i * 7) % 29) as f64;
+            for xt in [1, 2, 3, 4, 5, 6, 7, 9, 10] {
+                let full = compute_position(xt, t, 0.0, 0.0);
+                let p = with_precision(level, || compute_position(xt, t, 0.0, 0.0));
+                let d = math_utils::rad2rrad(p.2 - full.2).abs().max((p.3 - full.3).abs()) * arcsec;
+                if xt == 10 {
+                    max.1 = max.1.max(d);
+                } else {
+                    max.0 = max.0.max(d);
+                }
+            }
+        }
+        assert!(max.0 < planet && max.1 < moon, "level {} {:?}", level, max);
+    }
+    assert_eq!(precision(), 0);
+}
@@ -0,0 +1,253 @@
+use std::f64::consts::PI;
+use crate::internal::math_utils;
+
+// 30度
+const SIGN: f64 = PI / 6.0;
+
+/// 上升点
+///
+/// 由地方恒星时`ramc`、黄赤交角`e`和纬度`fa`计算上升点黄经
+pub fn asc(ramc: f64, e: f64, fa: f64) -> f64 {
+    let y = ramc.cos();
+    let x = -(ramc.sin() * e.cos() + fa.tan() * e.sin());
+    math_utils::rad2mrad(y.atan2(x))
+}
+
+/// 中天
+pub fn mc(ramc: f64, e: f64) -> f64 {
+    math_utils::rad2mrad(ramc.sin().atan2(ramc.cos() * e.cos()))
+}
+
+// 赤经为ra的时圈与黄道交点的黄经
+fn ra2lon(ra: f64, e: f64) -> f64 {
+    math_utils::rad2mrad(ra.sin().atan2(ra.cos() * e.cos()))
+}
+
+// 黄道上一点的赤纬
+fn lon2dec(lon: f64, e: f64) -> f64 {
+    (e.sin() * lon.sin()).asin()
+}
+
+// 半昼弧，拱极或者永不升起时返回None
+fn semi_diurnal_arc(dec: f64, fa: f64) -> Option<f64> {
+    let c = -fa.tan() * dec.tan();
+    if c.abs() > 1.0 { None } else { Some(c.acos()) }
+}
+
+// 以四个角点为基础，由东半球(第10、11、12、1、2、3宫)的宫头补全另外6宫
+fn complete(east: [f64; 6]) -> [f64; 12] {
+    let mut c = [0.0; 12];
+    for (i, x) in east.iter().enumerate() {
+        let h = (i + 9) % 12; // 第10宫下标为9
+        c[h] = math_utils::rad2mrad(*x);
+        c[(h + 6) % 12] = math_utils::rad2mrad(x + PI);
+    }
+    c
+}
+
+fn cross(a: (f64, f64, f64), b: (f64, f64, f64)) -> (f64, f64, f64) {
+    (a.1 * b.2 - a.2 * b.1, a.2 * b.0 - a.0 * b.2, a.0 * b.1 - a.1 * b.0)
+}
+
+// 极点为pole的大圆与黄道的交点黄经，取与side同侧的一个(赤道直角坐标)
+fn ecliptic_intersection(pole: (f64, f64, f64), side: (f64, f64, f64), e: f64) -> f64 {
+    let k = (0.0, -e.sin(), e.cos()); // 黄极
+    let mut d = cross(pole, k);
+    if d.0 * side.0 + d.1 * side.1 + d.2 * side.2 < 0.0 {
+        d = (-d.0, -d.1, -d.2);
+    }
+    // 转到黄道坐标
+    let y = d.1 * e.cos() + d.2 * e.sin();
+    math_utils::rad2mrad(y.atan2(d.0))
+}
+
+// 地平北点
+fn north_point(ramc: f64, fa: f64) -> (f64, f64, f64) {
+    (-fa.sin() * ramc.cos(), -fa.sin() * ramc.sin(), fa.cos())
+}
+
+// 过地平南北点的宫圈，与黄道交于宫头，p为宫圈上的另一点(赤道直角坐标)
+fn house_circle(p: (f64, f64, f64), n: (f64, f64, f64), e: f64) -> f64 {
+    ecliptic_intersection(cross(n, p), p, e)
+}
+
+/// 宿命点(Vertex)
+///
+/// 卯酉圈与黄道在西方的交点，对宫为反宿命点(Anti-Vertex)
+pub fn vertex(ramc: f64, e: f64, fa: f64) -> f64 {
+    let west = (ramc.sin(), -ramc.cos(), 0.0);
+    ecliptic_intersection(north_point(ramc, fa), west, e)
+}
+
+// 坎氏、雷氏宫制，p(k)返回第k个(0为第10宫)宫圈上的点
+fn great_circle_houses<F: Fn(f64) -> (f64, f64, f64)>(ramc: f64, e: f64, fa: f64, p: F) -> [f64; 12] {
+    let n = north_point(ramc, fa);
+    let mut east = [0.0; 6];
+    for (k, x) in east.iter_mut().enumerate() {
+        *x = match k {
+            0 => mc(ramc, e),
+            3 => asc(ramc, e, fa),
+            _ => house_circle(p((k as f64) * SIGN), n, e),
+        };
+    }
+    complete(east)
+}
+
+// 普拉西度宫制，迭代求半昼弧三分点
+fn placidus(ramc: f64, e: f64, fa: f64) -> Option<[f64; 12]> {
+    let mut east = [mc(ramc, e), 0.0, 0.0, asc(ramc, e, fa), 0.0, 0.0];
+    for (k, f) in [(1, 1.0 / 3.0), (2, 2.0 / 3.0), (4, 1.0 / 3.0), (5, 2.0 / 3.0)] {
+        let mut lon = ra2lon(ramc + (k as f64) * SIGN, e);
+        for _ in 0..50 {
+            let sda = semi_diurnal_arc(lon2dec(lon, e), fa)?;
+            let ra = if k < 3 { ramc + f * sda } else { ramc + sda + f * (PI - sda) };
+            let l = ra2lon(ra, e);
+            let d = math_utils::rad2rrad(l - lon).abs();
+            lon = l;
+            if d < 1e-10 {
+                break;
+            }
+        }
+        east[k] = lon;
+    }
+    Some(complete(east))
+}
+
+// 科赫宫制，中天度数半昼弧三分后求上升点
+fn koch(ramc: f64, e: f64, fa: f64) -> Option<[f64; 12]> {
+    let m = mc(ramc, e);
+    let d = semi_diurnal_arc(lon2dec(m, e), fa)?;
+    let t = [-d, (-2.0 * d) / 3.0, -d / 3.0, 0.0, d / 3.0, (2.0 * d) / 3.0];
+    let mut east = [0.0; 6];
+    for (k, x) in east.iter_mut().enumerate() {
+        *x = if k == 0 { m } else { asc(ramc + t[k], e, fa) };
+    }
+    Some(complete(east))
+}
+
+// 阿卡比特斯宫制，上升点度数的半昼弧三分后沿时圈投影
+fn alcabitius(ramc: f64, e: f64, fa: f64) -> Option<[f64; 12]> {
+    let a = asc(ramc, e, fa);
+    let sda = semi_diurnal_arc(lon2dec(a, e), fa)?;
+    let sna = PI - sda;
+    let ra = [0.0, sda / 3.0, (2.0 * sda) / 3.0, 0.0, sda + sna / 3.0, sda + (2.0 * sna) / 3.0];
+    let mut east = [0.0; 6];
+    for (k, x) in east.iter_mut().enumerate() {
+        *x = match k {
+            0 => mc(ramc, e),
+            3 => a,
+            _ => ra2lon(ramc + ra[k], e),
+        };
+    }
+    Some(complete(east))
+}
+
+// 波菲利宫制，四个象限等分黄经
+fn porphyry(ramc: f64, e: f64, fa: f64) -> [f64; 12] {
+    let m = mc(ramc, e);
+    let a = asc(ramc, e, fa);
+    let q1 = math_utils::rad2mrad(a - m); // 第10宫到第1宫
+    let q2 = PI - q1; // 第1宫到第4宫
+    complete([m, m + q1 / 3.0, m + (2.0 * q1) / 3.0, a, a + q2 / 3.0, a + (2.0 * q2) / 3.0])
+}
+
+/// 宫头计算
+///
+/// 返回第1宫到第12宫宫头黄经，普拉西度、科赫、阿卡比特斯在极区半昼弧不存在时返回`None`
+/// # Argument
+/// - `system`: 0:普拉西度， 1:科赫， 2:雷格蒙塔努斯， 3:坎帕努斯， 4:波菲利， 5:等宫， 6:整宫，
+///   7:阿卡比特斯， 8:莫里努斯
+/// - `ramc`: 地方恒星时
+/// - `e`: 黄赤交角
+/// - `fa`: 纬度
+pub fn house_cusps(system: usize, ramc: f64, e: f64, fa: f64) -> Option<[f64; 12]> {
+    let cusps = match system {
+        0 => placidus(ramc, e, fa)?,
+        1 => koch(ramc, e, fa)?,
+        2 => {
+            // 赤道上子午圈交点向东等分
+            let q = (ramc.cos(), ramc.sin(), 0.0);
+            let east = (-ramc.sin(), ramc.cos(), 0.0);
+            great_circle_houses(ramc, e, fa, |a| {
+                (q.0 * a.cos() + east.0 * a.sin(), q.1 * a.cos() + east.1 * a.sin(), 0.0)
+            })
+        }
+        3 => {
+            // 卯酉圈上天顶向东等分
+            let z = (fa.cos() * ramc.cos(), fa.cos() * ramc.sin(), fa.sin());
+            let east = (-ramc.sin(), ramc.cos(), 0.0);
+            great_circle_houses(ramc, e, fa, |a| {
+                (
+                    z.0 * a.cos() + east.0 * a.sin(),
+                    z.1 * a.cos() + east.1 * a.sin(),
+                    z.2 * a.cos(),
+                )
+            })
+        }
+        4 => porphyry(ramc, e, fa),
+        5 => {
+            let a = asc(ramc, e, fa);
+            core::array::from_fn(|i| math_utils::rad2mrad(a + (i as f64) * SIGN))
+        }
+        6 => {
+            let a = (asc(ramc, e, fa) / SIGN).floor() * SIGN;
+            core::array::from_fn(|i| math_utils::rad2mrad(a + (i as f64) * SIGN))
+        }
+        7 => alcabitius(ramc, e, fa)?,
+        8 => {
+            // 赤道等分点沿黄经圈投影到黄道，第10宫一般不与中天重合
+            let mut c = [0.0; 12];
+            for (i, x) in c.iter_mut().enumerate() {
+                let a = ramc + ((i as f64) - 9.0) * SIGN;
+                *x = math_utils::rad2mrad((a.sin() * e.cos()).atan2(a.cos()));
+            }
+            c
+        }
+        _ => panic!("invalid house system {}", system),
+    };
+    Some(cusps)
+}
+
+#[test]
+fn test_house_cusps() {
+    let e = 23.4392911_f64.to_radians();
+    let ramc = 1.2;
+
+    // 赤道上各象限宫制结果相同
+    let base = house_cusps(0, ramc, e, 0.0).unwrap();
+    for s in [1, 2, 3, 7] {
+        let c = house_cusps(s, ramc, e, 0.0).unwrap();
+        for i in 0..12 {
+            assert!(math_utils::rad2rrad(c[i] - base[i]).abs() < 1e-8, "system {} house {}", s, i + 1);
+        }
+    }
+
+    // 第1、10宫为上升点和中天
+    let fa = 40.0_f64.to_radians();
+    for s in [0, 1, 2, 3, 4, 7] {
+        let c = house_cusps(s, ramc, e, fa).unwrap();
+        assert!(math_utils::rad2rrad(c[0] - asc(ramc, e, fa)).abs() < 1e-9);
+        assert!(math_utils::rad2rrad(c[9] - mc(ramc, e)).abs() < 1e-9);
+    }
+
+    // 普拉西度第11宫宫头时角为其半昼弧的1/3
+    let c = house_cusps(0, ramc, e, fa).unwrap();
+    let ra = (c[10].sin() * e.cos()).atan2(c[10].cos());
+    let h = math_utils::rad2rrad(ra - ramc);
+    assert!((h - semi_diurnal_arc(lon2dec(c[10], e), fa).unwrap() / 3.0).abs() < 1e-8);
+
+    // 整宫从上升星座0度开始
+    let c = house_cusps(6, ramc, e, fa).unwrap();
+    assert!((c[0] / SIGN).fract().abs() < 1e-12);
+
+    // 北纬70度部分时刻普拉西度、科赫无解
+    let polar = (0..360).any(|i| house_cusps(0, (i as f64).to_radians(), e, 70.0_f64.to_radians()).is_none());
+    assert!(polar);
+
+    // 宿命点在卯酉圈上，与用余纬计算天底上升点的方法结果相同
+    let v = vertex(ramc, e, fa);
+    assert!(math_utils::rad2rrad(v - asc(ramc + PI, e, PI / 2.0 - fa)).abs() < 1e-9);
+    let p = (v.cos(), v.sin() * e.cos(), v.sin() * e.sin());
+    let n = north_point(ramc, fa);
+    assert!((p.0 * n.0 + p.1 * n.1 + p.2 * n.2).abs() < 1e-12);
+}
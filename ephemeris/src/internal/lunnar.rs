@@ -3,6 +3,7 @@ use std::f64::consts::PI;
 use crate::internal::math_utils;
 use crate::internal::ephemeris;
 use crate::internal::constants;
+use crate::time::{ Instant, TimeScale };
 
 #[derive(Debug, Default)]
 pub struct YearCalender {
@@ -220,6 +221,26 @@ impl JulianDate {
         }
     }
 
+    /// 按指定时间尺度解释该儒略日
+    pub fn instant(&self, scale: TimeScale) -> Instant {
+        Instant::new(self.jd, scale)
+    }
+
+    /// 由时刻构造，先转换到时间尺度`scale`再取儒略日
+    ///
+    /// # Example
+    /// ```
+    /// use rust_ephemeris::JulianDate;
+    /// use rust_ephemeris::time::*;
+    /// let t = Instant::from_day(2017, 1, 1.0, TimeScale::UTC);
+    /// assert_eq!(JulianDate::from_instant(t, TimeScale::UTC).jd, t.jd());
+    /// let tt = JulianDate::from_instant(t, TimeScale::TT);
+    /// assert!(((tt.jd - t.jd()) * 86400.0 - 69.184).abs() < 1e-3);
+    /// ```
+    pub fn from_instant(t: Instant, scale: TimeScale) -> Self {
+        JulianDate::new(t.to(scale).jd())
+    }
+
 
     /// 儒略日转为日期
     pub fn jd2day(x: f64) -> (i32, i32, f64) {
@@ -442,6 +463,42 @@ fn test_so() {
     assert!((so_low(l_w) - l_r).abs() < 1e-6);
 }
 
+/// 月相精确时刻
+///
+/// `k`是月相序数(Meeus),k=0为2000-01-06的朔，`phase`是 0:朔， 1:上弦， 2:望， 3:下弦,
+/// 返回东八区时间，J2000起算的日数
+pub fn moon_phase_accurate(k: i32, phase: usize) -> f64 {
+    so_accurate(((k as f64) + (phase as f64) / 4.0) * PI * 2.0)
+}
+
+/// 月相序数
+///
+/// 返回`jd`(东八区，J2000起算)之前最近一次朔的序数(Meeus)
+pub fn lunation_number(jd: f64) -> i32 {
+    let mut k = ((jd - 5.6) / 29.530588853).floor() as i32;
+    if moon_phase_accurate(k, 0) > jd {
+        k -= 1;
+    } else if moon_phase_accurate(k + 1, 0) <= jd {
+        k += 1;
+    }
+    k
+}
+
+#[test]
+fn test_moon_phase_accurate() {
+    // 2023-8 朔:16日17:38 上弦:24日17:57 望:31日09:35 下弦:9月7日06:21(东八区)
+    let k = lunation_number(8631.0);
+    assert_eq!(k, 292);
+    let exp_ = [8628.2347, 8636.2479, 8642.8993, 8649.7646];
+    for (i, e) in exp_.iter().enumerate() {
+        let r = moon_phase_accurate(k, i);
+        println!("exp={}, r={}", e, r);
+        assert!((r - e).abs() < 2.0 / 1440.0);
+    }
+    assert_eq!(lunation_number(8628.2), 291);
+    assert_eq!(lunation_number(8628.3), 292);
+}
+
 fn qi_low(w: f64) -> f64 {
     let mut t = (w - 4.895062166) / 628.3319653318; // 第一次估算
     t -=
@@ -1,5 +1,6 @@
 #[allow(dead_code)]
 use std::f64::consts::PI;
+use std::cell::RefCell;
 use crate::internal::constants;
 
 /// 角度
@@ -128,18 +129,24 @@ fn dt_ext(y: f64, jsd: f64) -> f64 {
     -20.0 + jsd * dy.powi(2)
 }
 
+// 表外按加速度jsd的二次曲线外推，与表端点y0(值t0)相距100年内线性过渡，保证连续
+fn dt_blend(y: f64, y0: f64, t0: f64, jsd: f64) -> f64 {
+    let v = dt_ext(y, jsd);
+    let d = (y - y0).abs();
+    if d > 100.0 {
+        return v;
+    }
+    let dv = dt_ext(y0, jsd) - t0; // y0年的二次外推与t0的差
+    v - (dv * (100.0 - d)) / 100.0
+}
+
 fn dt_calc(y: f64) -> f64 {
     let dt_at_len = constants::DT_AT.len();
     let y0 = constants::DT_AT[dt_at_len - 2]; // 表中最后一年
     let t0 = constants::DT_AT[dt_at_len - 1]; // 表中最后一年的deltatT
     if y >= y0 {
         let jsd = 31.0; // sjd是y1年之后的加速度估计。瑞士星历表jsd=31,NASA网站jsd=32,skmap的jsd=29
-        if y > y0 + 100.0 {
-            return dt_ext(y, jsd);
-        }
-        let v = dt_ext(y, jsd); // 二次曲线外推
-        let dv = dt_ext(y0, jsd) - t0; // ye年的二次外推与te的差
-        return v - (dv * (y0 + 100.0 - y)) / 100.0;
+        return dt_blend(y, y0, t0, jsd);
     }
     let d = &constants::DT_AT;
     for i in (0..d.len()).step_by(5) {
@@ -153,13 +160,139 @@ fn dt_calc(y: f64) -> f64 {
     0.0
 }
 
+// Morrison & Stephenson(2004)表1，-500年至1600年每100年的ΔT(秒)
+// (L. V. Morrison, F. R. Stephenson, Historical values of the Earth's clock error ΔT
+// and the calculation of eclipses, J. Hist. Astron. 35 (2004) 327-336)
+const DT_MS2004: [f64; 22] = [
+    17190.0, 15530.0, 14080.0, 12790.0, 11640.0, 10580.0, 9600.0, 8640.0, 7680.0, 6700.0, 5710.0,
+    4740.0, 3810.0, 2960.0, 2200.0, 1570.0, 1090.0, 740.0, 490.0, 320.0, 200.0, 120.0,
+];
+
+// Morrison & Stephenson(2004)：-500年至1600年按表1三次插值，-500年以前为长期抛物线-20+32u²
+// (与表端点相距100年内线性过渡)，1600年以后取望远镜观测时代的实测值(内置表，1600年为120秒，与表1相接)
+fn dt_morrison_stephenson(y: f64) -> f64 {
+    if y >= 1600.0 {
+        return dt_calc(y);
+    }
+    if y < -500.0 {
+        return dt_blend(y, -500.0, DT_MS2004[0], 32.0);
+    }
+    // 取相邻4个表值做拉格朗日插值，两端取最近的4个
+    let x = (y + 500.0) / 100.0;
+    let i = (x.floor() as usize).clamp(1, DT_MS2004.len() - 3) - 1;
+    (i..i + 4).fold(0.0, |s, j| {
+        let w = (i..i + 4)
+            .filter(|k| *k != j)
+            .fold(1.0, |w, k| (w * (x - (k as f64))) / ((j as f64) - (k as f64)));
+        s + w * DT_MS2004[j]
+    })
+}
+
+fn poly(t: f64, c: &[f64]) -> f64 {
+    c.iter().rev().fold(0.0, |s, x| s * t + x)
+}
+
+// Espenak & Meeus(2006)多项式，NASA日月食表使用
+fn dt_espenak_meeus(y: f64) -> f64 {
+    let u = (y - 1820.0) / 100.0;
+    match y {
+        y if y < -500.0 => -20.0 + 32.0 * u * u,
+        y if y < 500.0 => poly(
+            y / 100.0,
+            &[10583.6, -1014.41, 33.78311, -5.952053, -0.1798452, 0.022174192, 0.0090316521]
+        ),
+        y if y < 1600.0 => poly(
+            (y - 1000.0) / 100.0,
+            &[1574.2, -556.01, 71.23472, 0.319781, -0.8503463, -0.005050998, 0.0083572073]
+        ),
+        y if y < 1700.0 => poly(y - 1600.0, &[120.0, -0.9808, -0.01532, 1.0 / 7129.0]),
+        y if y < 1800.0 => poly(y - 1700.0, &[8.83, 0.1603, -0.0059285, 0.00013336, -1.0 / 1174000.0]),
+        y if y < 1860.0 => poly(
+            y - 1800.0,
+            &[
+                13.72, -0.332447, 0.0068612, 0.0041116, -0.00037436, 0.0000121272, -0.0000001699,
+                0.000000000875,
+            ]
+        ),
+        y if y < 1900.0 => poly(
+            y - 1860.0,
+            &[7.62, 0.5737, -0.251754, 0.01680668, -0.0004473624, 1.0 / 233174.0]
+        ),
+        y if y < 1920.0 => poly(y - 1900.0, &[-2.79, 1.494119, -0.0598939, 0.0061966, -0.000197]),
+        y if y < 1941.0 => poly(y - 1920.0, &[21.2, 0.84493, -0.0761, 0.0020936]),
+        y if y < 1961.0 => poly(y - 1950.0, &[29.07, 0.407, -1.0 / 233.0, 1.0 / 2547.0]),
+        y if y < 1986.0 => poly(y - 1975.0, &[45.45, 1.067, -1.0 / 260.0, -1.0 / 718.0]),
+        y if y < 2005.0 => poly(
+            y - 2000.0,
+            &[63.86, 0.3345, -0.060374, 0.0017275, 0.000651814, 0.00002373599]
+        ),
+        y if y < 2050.0 => poly(y - 2000.0, &[62.92, 0.32217, 0.005589]),
+        y if y < 2150.0 => -20.0 + 32.0 * u * u - 0.5628 * (2150.0 - y),
+        _ => -20.0 + 32.0 * u * u,
+    }
+}
+
+// 用户表，表内线性插值，表外按加速度31二次外推
+fn dt_table(y: f64, table: &[(f64, f64)]) -> f64 {
+    let (first, last) = (table[0], table[table.len() - 1]);
+    if y <= first.0 {
+        return dt_blend(y, first.0, first.1, 31.0);
+    }
+    if y >= last.0 {
+        return dt_blend(y, last.0, last.1, 31.0);
+    }
+    let i = table.partition_point(|x| x.0 <= y);
+    let (a, b) = (table[i - 1], table[i]);
+    a.1 + ((b.1 - a.1) * (y - a.0)) / (b.0 - a.0)
+}
+
+thread_local! {
+    // 当前线程的ΔT模型及用户表，见delta_t
+    static DT_MODEL: RefCell<(usize, Vec<(f64, f64)>)> = const { RefCell::new((0, Vec::new())) };
+}
+
+/// 在`f`执行期间使用ΔT模型`model`，只对当前线程有效，返回(包括panic)后恢复原模型
+///
+/// 0:内置表， 1:Morrison-Stephenson 2004， 2:Espenak-Meeus 2006， 3:用户表`table`，其余按0处理。
+/// 用户表为(年, ΔT秒)，会按年份排序，有效项少于2项时按内置表计算
+pub fn with_dt_model<R>(model: usize, mut table: Vec<(f64, f64)>, f: impl FnOnce() -> R) -> R {
+    struct Restore((usize, Vec<(f64, f64)>));
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let old = std::mem::take(&mut self.0);
+            DT_MODEL.with(|m| *m.borrow_mut() = old);
+        }
+    }
+    table.retain(|x| x.0.is_finite() && x.1.is_finite());
+    table.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let model = if model == 3 && table.len() < 2 { 0 } else { model };
+    let _restore = Restore(DT_MODEL.with(|m| m.replace((model, table))));
+    f()
+}
+
+/// 按当前线程的模型计算ΔT(TT-UT)，单位秒
+///
+/// # Argument
+/// - `y`: 年份，带小数
+pub fn delta_t(y: f64) -> f64 {
+    DT_MODEL.with(|m| {
+        let m = m.borrow();
+        match m.0 {
+            1 => dt_morrison_stephenson(y),
+            2 => dt_espenak_meeus(y),
+            3 => dt_table(y, &m.1),
+            _ => dt_calc(y),
+        }
+    })
+}
+
 /// TD-UT计算
 /// 
-/// 注意是修正儒略日之间误差，内部使用
+/// 注意是修正儒略日之间误差，内部使用，按当前ΔT模型计算
 /// # Argument
 /// - `t`: 相对于J2000的天数
 pub fn dt_t(t: f64) -> f64 {
-    dt_calc(t / 365.2425 + 2000.0) / 86400.0
+    delta_t(t / 365.2425 + 2000.0) / 86400.0
 }
 
 #[test]
@@ -167,6 +300,45 @@ fn test_dt_t() {
     println!("{:?}", dt_t(3062.49987811566)); // 0.0007605955088259062
 }
 
+#[test]
+fn test_dt_models() {
+    // Espenak-Meeus 2006 多项式在2000、1900年的值
+    assert!((dt_espenak_meeus(2000.0) - 63.86).abs() < 1e-9);
+    assert!((dt_espenak_meeus(1900.0) + 2.79).abs() < 1e-9);
+    // 与内置表在近代相差不超过2秒
+    for y in [1650.0, 1750.0, 1850.0, 1950.0, 2000.0] {
+        assert!((dt_espenak_meeus(y) - dt_calc(y)).abs() < 2.0, "{}", y);
+    }
+    // Morrison-Stephenson 2004表1的值：-500年17190秒，0年10580秒，1000年1570秒
+    // (其拟合多项式在1000年为1574秒)
+    assert!((dt_morrison_stephenson(-500.0) - 17190.0).abs() < 1e-6);
+    assert!((dt_morrison_stephenson(0.0) - 10580.0).abs() < 1e-6);
+    assert!((dt_morrison_stephenson(1000.0) - 1574.0).abs() < 5.0);
+    assert!((dt_morrison_stephenson(1500.0) - 200.0).abs() < 1e-6);
+    // 表值之间与Espenak-Meeus多项式(由该表拟合)相差不超过1%或5秒
+    for i in 0..210 {
+        let y = -495.0 + (i as f64) * 10.0;
+        let d = dt_morrison_stephenson(y) - dt_espenak_meeus(y);
+        assert!(d.abs() < 0.01 * dt_espenak_meeus(y).max(500.0), "{} {}", y, d);
+    }
+    // 1600年与实测值相接，-600年以前为抛物线
+    assert!((dt_morrison_stephenson(1600.0 - 1e-9) - dt_calc(1600.0)).abs() < 1e-6);
+    assert!((dt_morrison_stephenson(-500.0 - 1e-9) - 17190.0).abs() < 1e-3);
+    assert!((dt_morrison_stephenson(-1000.0) - dt_ext(-1000.0, 32.0)).abs() < 1e-9);
+
+    // 模型只在with_dt_model期间有效
+    let y = 2000.0;
+    assert!((with_dt_model(2, Vec::new(), || delta_t(y)) - 63.86).abs() < 1e-9);
+    assert_eq!(delta_t(y), dt_calc(y));
+    // 用户表不足2项时按内置表
+    assert_eq!(with_dt_model(3, vec![(2000.0, 0.0)], || delta_t(y)), dt_calc(y));
+    // 用户表线性插值，表外连续外推
+    let table = [(2000.0, 64.0), (2010.0, 66.0), (2020.0, 69.0)];
+    assert!((dt_table(2015.0, &table) - 67.5).abs() < 1e-9);
+    assert!((dt_table(2020.0 + 1e-9, &table) - 69.0).abs() < 1e-6);
+    assert!((dt_table(2200.0, &table) - dt_ext(2200.0, 31.0)).abs() < 1e-9);
+}
+
 /// 坐标系转换
 
 /// 直角转为球坐标
@@ -226,4 +398,72 @@ pub fn rad2rrad( v: f64) -> f64 {
         return v - 2.0 * PI;
     }
     v
-}
\ No newline at end of file
+}
+/// 求函数在区间内的全部零点
+///
+/// 按步长`step`对`f`采样，发现变号后用试位法(Illinois)加密求解。
+/// 步长内出现两个零点时会漏判，步长需要小于零点的最小间隔
+///
+/// # Argument
+/// - `f`: 待求零点的函数
+/// - `t0`, `t1`: 求解区间
+/// - `step`: 采样步长
+/// - `returns`: `(零点, 是否由负变正)`，按时间先后排列
+pub fn find_roots<F: FnMut(f64) -> f64>(mut f: F, t0: f64, t1: f64, step: f64) -> Vec<(f64, bool)> {
+    let mut roots = Vec::new();
+    let n = ((t1 - t0) / step).ceil().max(1.0) as usize;
+    let h = (t1 - t0) / (n as f64);
+    let mut a = t0;
+    let mut fa = f(a);
+    for i in 1..=n {
+        let b = t0 + h * (i as f64);
+        let fb = f(b);
+        if fa == 0.0 {
+            roots.push((a, fb > 0.0));
+        } else if fa * fb < 0.0 {
+            roots.push((refine_root(&mut f, a, b, fa, fb), fb > 0.0));
+        }
+        a = b;
+        fa = fb;
+    }
+    roots
+}
+
+// 试位法求根，要求f(a)与f(b)异号
+fn refine_root<F: FnMut(f64) -> f64>(f: &mut F, mut a: f64, mut b: f64, mut fa: f64, mut fb: f64) -> f64 {
+    let mut side = 0;
+    let mut last = a;
+    for _ in 0..60 {
+        let c = (a * fb - b * fa) / (fb - fa);
+        let fc = f(c);
+        if fc == 0.0 || (c - last).abs() < 1e-9 {
+            return c;
+        }
+        last = c;
+        if fc * fb > 0.0 {
+            b = c;
+            fb = fc;
+            if side == -1 {
+                fa /= 2.0;
+            }
+            side = -1;
+        } else {
+            a = c;
+            fa = fc;
+            if side == 1 {
+                fb /= 2.0;
+            }
+            side = 1;
+        }
+    }
+    (a * fb - b * fa) / (fb - fa)
+}
+
+#[test]
+fn test_find_roots() {
+    let r = find_roots(|x| x.sin(), 0.5, 10.0, 0.5);
+    assert_eq!(r.len(), 3);
+    assert!((r[0].0 - PI).abs() < 1e-8 && !r[0].1);
+    assert!((r[1].0 - 2.0 * PI).abs() < 1e-8 && r[1].1);
+    assert!((r[2].0 - 3.0 * PI).abs() < 1e-8);
+}
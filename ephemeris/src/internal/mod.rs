@@ -3,5 +3,11 @@ pub mod math_utils;
 pub mod ephemeris;
 pub mod constants;
 pub mod lunnar;
-
-
+pub mod rise_set;
+pub mod eclipse;
+pub mod phenomena;
+pub mod siyu;
+pub mod houses;
+pub mod xiu;
+pub mod time_scale;
+pub mod physical;
@@ -0,0 +1,295 @@
+use crate::internal::math_utils;
+use crate::internal::ephemeris::{ compute_position, compute_speed };
+use crate::internal::eclipse::separation;
+
+// 搜索采样步长（日），水星两次相邻事件间隔也远大于2天
+const STEP: f64 = 1.0;
+
+// 数值求导的半步长（日）
+const DH: f64 = 0.01;
+
+// 地心视黄经、视黄纬、地心距
+fn apparent(xt: usize, t: f64) -> (f64, f64, f64) {
+    let (_, _, a_lon, a_lat, _, _, _, d_e, _, _, _, _, _, _, _) = compute_position(xt, t, 0.0, 0.0);
+    (a_lon, a_lat, d_e)
+}
+
+/// 行星与太阳的视黄经差，在$-\pi-\pi$之间，正值表示行星在太阳东边
+pub fn lon_diff(xt: usize, t: f64) -> f64 {
+    math_utils::rad2rrad(apparent(xt, t).0 - apparent(9, t).0)
+}
+
+/// 行星距角，即行星与太阳的角距
+pub fn elongation(xt: usize, t: f64) -> f64 {
+    let p = apparent(xt, t);
+    let s = apparent(9, t);
+    separation((p.0, p.1), (s.0, s.1))
+}
+
+/// 行星天象
+///
+/// 搜索力学时`t0`到`t1`之间行星的合、冲、大距和留，按时间排序
+/// 返回(力学时, 类型, 行星视黄经)列表, 类型:
+/// 0:合(外行星)， 1:下合， 2:上合， 3:冲， 4:东大距， 5:西大距， 6:留(顺转逆)， 7:留(逆转顺)
+/// # Argument
+/// - `xt`: 星体序号，1-8(水星到冥王星)
+pub fn planet_phenomena(xt: usize, t0: f64, t1: f64) -> Vec<(f64, usize, f64)> {
+    let inner = xt == 1 || xt == 2;
+    let mut r = Vec::new();
+
+    // 合、冲：黄经差的正弦为零
+    for (t, _) in math_utils::find_roots(|t| lon_diff(xt, t).sin(), t0, t1, STEP) {
+        let kind = if lon_diff(xt, t).cos() < 0.0 {
+            3
+        } else if !inner {
+            0
+        } else if apparent(xt, t).2 < apparent(9, t).2 {
+            1
+        } else {
+            2
+        };
+        r.push((t, kind));
+    }
+
+    // 大距：距角的极大值
+    if inner {
+        let f = |t: f64| elongation(xt, t + DH) - elongation(xt, t - DH);
+        for (t, rising) in math_utils::find_roots(f, t0, t1, STEP) {
+            if !rising {
+                r.push((t, if lon_diff(xt, t) > 0.0 { 4 } else { 5 }));
+            }
+        }
+    }
+
+    // 留：视黄经速度为零
+    for (t, rising) in math_utils::find_roots(|t| compute_speed(xt, t).0, t0, t1, STEP) {
+        r.push((t, if rising { 7 } else { 6 }));
+    }
+
+    r.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    r.into_iter()
+        .map(|(t, kind)| (t, kind, apparent(xt, t).0))
+        .collect()
+}
+
+/// 月亮天象
+///
+/// 搜索力学时`t0`到`t1`之间月亮的近地点、远地点、升降交点以及赤纬极值，按时间排序
+/// 返回(力学时, 类型)列表, 类型:
+/// 0:近地点， 1:远地点， 2:升交点， 3:降交点， 4:赤纬北极值， 5:赤纬南极值
+pub fn moon_events(t0: f64, t1: f64) -> Vec<(f64, usize)> {
+    let mut r = Vec::new();
+
+    // 近、远地点：地心距变化为零
+    for (t, rising) in math_utils::find_roots(|t| compute_speed(10, t).2, t0, t1, STEP) {
+        r.push((t, if rising { 0 } else { 1 }));
+    }
+
+    // 升、降交点：视黄纬为零
+    for (t, rising) in math_utils::find_roots(|t| apparent(10, t).1, t0, t1, STEP) {
+        r.push((t, if rising { 2 } else { 3 }));
+    }
+
+    // 赤纬极值
+    let dec = |t: f64| compute_position(10, t, 0.0, 0.0).5;
+    for (t, rising) in math_utils::find_roots(|t| dec(t + DH) - dec(t - DH), t0, t1, STEP) {
+        r.push((t, if rising { 5 } else { 4 }));
+    }
+
+    r.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    r
+}
+
+/// 星体视坐标
+/// # Argument
+/// - `kind`: 0:视黄经， 1:视赤纬， 2:视赤经
+pub fn coordinate(xt: usize, kind: usize, t: f64) -> f64 {
+    let p = compute_position(xt, t, 0.0, 0.0);
+    match kind {
+        0 => p.2,
+        1 => p.5,
+        2 => p.4,
+        _ => panic!("invalid coordinate kind {}", kind),
+    }
+}
+
+/// 星体视坐标到达指定值的时刻
+///
+/// 搜索力学时`t0`到`t1`之间星体视黄经(或视赤纬、视赤经)等于`target`的所有时刻，逆行时可能有多个解。
+/// 先以坐标的极值(留)为界分段，每段内坐标单调，留附近相距很近的两个解也能分开
+/// 返回(力学时, 到达时坐标是否增大)列表
+/// # Argument
+/// - `kind`: 0:视黄经， 1:视赤纬， 2:视赤经
+pub fn crossings(xt: usize, kind: usize, target: f64, t0: f64, t1: f64) -> Vec<(f64, bool)> {
+    let g = |t: f64| {
+        let v = coordinate(xt, kind, t);
+        if kind == 1 { v - target } else { math_utils::rad2rrad(v - target) }
+    };
+    let dg = |t: f64| {
+        let d = coordinate(xt, kind, t + DH) - coordinate(xt, kind, t - DH);
+        if kind == 1 { d } else { math_utils::rad2rrad(d) }
+    };
+
+    let mut edges = vec![t0];
+    edges.extend(math_utils::find_roots(dg, t0, t1, STEP).into_iter().map(|x| x.0));
+    edges.push(t1);
+
+    let mut r = Vec::new();
+    for w in edges.windows(2) {
+        // 黄经、赤经在相差180度处也会变号，需要排除
+        for (t, rising) in math_utils::find_roots(g, w[0], w[1], STEP) {
+            if g(t).abs() < 1e-6 && !r.iter().any(|x: &(f64, bool)| (x.0 - t).abs() < 1e-6) {
+                r.push((t, rising));
+            }
+        }
+    }
+    r
+}
+
+/// 星体视黄经经过一组分界点的时刻
+///
+/// 用于星座、宿的入宫计算。以留为界分段，段内按步长采样，相邻两次采样之间扫过的分界点逐一求解，
+/// 很窄的分界(如觜宿)也不会漏掉。返回(力学时, 分界序号, 视黄经是否增大)列表，按时间排序
+/// # Argument
+/// - `n`: 分界点个数
+/// - `bound`: `bound(i, t)`返回力学时`t`(J2000起算的儒略世纪数)第`i`个分界点的视黄经
+pub fn ingresses<F: Fn(usize, f64) -> f64>(xt: usize, n: usize, bound: F, t0: f64, t1: f64) -> Vec<(f64, usize, bool)> {
+    let lon = |t: f64| coordinate(xt, 0, t);
+    let mut edges = vec![t0];
+    edges.extend(math_utils::find_roots(|t| compute_speed(xt, t).0, t0, t1, STEP).into_iter().map(|x| x.0));
+    edges.push(t1);
+
+    let mut r = Vec::new();
+    for w in edges.windows(2) {
+        let m = ((w[1] - w[0]) / STEP).ceil().max(1.0) as usize;
+        let h = (w[1] - w[0]) / (m as f64);
+        let mut a = w[0];
+        let mut la = lon(a);
+        for k in 1..=m {
+            let b = w[0] + h * (k as f64);
+            let lb = lon(b);
+            let d = math_utils::rad2rrad(lb - la);
+            for i in 0..n {
+                let c = bound(i, (a + b) / 2.0 / 36525.0);
+                // 分界点在本步扫过的弧段内，起点不计以免重复
+                let x = if d > 0.0 { math_utils::rad2mrad(c - la) } else { math_utils::rad2mrad(la - c) };
+                if x == 0.0 || x > d.abs() {
+                    continue;
+                }
+                let f = |t: f64| math_utils::rad2rrad(lon(t) - bound(i, t / 36525.0));
+                if let Some((t, _)) = math_utils::find_roots(f, a, b, b - a).into_iter().next() {
+                    r.push((t, i, d > 0.0));
+                }
+            }
+            a = b;
+            la = lb;
+        }
+    }
+    r.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    r
+}
+
+#[test]
+fn test_planet_phenomena() {
+    // 2023年 力学时与UT相差约69秒，这里不区分
+    let day = |m: f64, d: f64| {
+        let days = [0.0, 31.0, 59.0, 90.0, 120.0, 151.0, 181.0, 212.0, 243.0, 273.0, 304.0, 334.0];
+        8400.5 + days[(m as usize) - 1] + d - 1.0
+    };
+    let find = |xt: usize, kind: usize| -> Vec<f64> {
+        planet_phenomena(xt, day(1.0, 1.0), day(12.0, 31.0))
+            .into_iter()
+            .filter(|x| x.1 == kind)
+            .map(|x| x.0)
+            .collect()
+    };
+
+    // 金星下合 2023-08-13 11:16 UT
+    let v = find(2, 1);
+    assert_eq!(v.len(), 1);
+    assert!((v[0] - (day(8.0, 13.0) + 11.27 / 24.0)).abs() < 0.05);
+
+    // 木星冲 2023-11-03 05:03 UT
+    let v = find(4, 3);
+    assert!((v[0] - (day(11.0, 3.0) + 5.05 / 24.0)).abs() < 0.05);
+
+    // 火星合 2023-11-18
+    let v = find(3, 0);
+    assert!((v[0] - day(11.0, 18.5)).abs() < 0.5);
+
+    // 水星东大距 2023-04-11 距角19.5度, 留 2023-04-21
+    let v = find(1, 4);
+    println!("{:?}", v);
+    let t = *v.iter().find(|&&t| (t - day(4.0, 11.9)).abs() < 0.5).unwrap();
+    assert!((elongation(1, t).to_degrees() - 19.5).abs() < 0.1);
+    let v = find(1, 6);
+    println!("{:?}", v);
+    assert!(v.iter().any(|&t| (t - day(4.0, 21.36)).abs() < 0.5));
+    assert_eq!(find(1, 7).len(), v.len());
+}
+
+#[test]
+fn test_moon_events() {
+    // 2023-08 近地点 08-02 05:51, 08-30 15:54 357181千米; 远地点 08-16 11:54
+    let t0 = 8612.5;
+    let r = moon_events(t0, t0 + 31.0);
+    let find = |kind: usize| -> Vec<f64> { r.iter().filter(|x| x.1 == kind).map(|x| x.0).collect() };
+    let perigee = find(0);
+    let apogee = find(1);
+    println!("{:?} {:?}", perigee, apogee);
+    for (t, d) in [(perigee[0], 8613.5 + 5.85 / 24.0), (perigee[1], 8641.5 + 15.9 / 24.0), (apogee[0], 8627.5 + 11.9 / 24.0)] {
+        assert!((t - d).abs() < 0.1);
+    }
+    let d = compute_position(10, perigee[1], 0.0, 0.0).7;
+    assert!((d - 357181.0).abs() < 5.0);
+
+    // 交点处黄纬为零，赤纬极值相隔约半个月
+    for t in find(2).iter().chain(find(3).iter()) {
+        assert!(apparent(10, *t).1.abs() < 1e-7);
+    }
+    let n = find(4);
+    let s = find(5);
+    assert!(!n.is_empty() && !s.is_empty());
+    assert!(((n[0] - s[0]).abs() - 13.66).abs() < 1.5);
+}
+
+#[test]
+fn test_crossings() {
+    // 2023年春分 03-20 21:24 UT
+    let r = crossings(9, 0, 0.0, 8400.5 + 60.0, 8400.5 + 90.0);
+    assert_eq!(r.len(), 1);
+    assert!((r[0].0 - (8478.5 + 21.4 / 24.0)).abs() < 0.01);
+    assert!(r[0].1);
+
+    // 水星2023-04-21留后逆行，同一黄经在逆行前后三次到达
+    let t0 = 8400.5 + 90.0;
+    let lon = coordinate(1, 0, 8400.5 + 105.0);
+    let r = crossings(1, 0, lon, t0, t0 + 75.0);
+    assert_eq!(r.len(), 3);
+    assert_eq!(r.iter().filter(|x| !x.1).count(), 1);
+    for (t, _) in r.iter() {
+        assert!(math_utils::rad2rrad(coordinate(1, 0, *t) - lon).abs() < 1e-7);
+    }
+
+    // 月亮赤纬一个月两次经过0度
+    let r = crossings(10, 1, 0.0, t0, t0 + 27.3);
+    assert_eq!(r.len(), 2);
+}
+
+#[test]
+fn test_ingresses() {
+    // 2023年太阳进入白羊座即春分
+    let t0 = 8400.5;
+    let r = ingresses(9, 12, |i, _| (i as f64) * std::f64::consts::PI / 6.0, t0, t0 + 365.0);
+    assert_eq!(r.len(), 12);
+    assert_eq!(r[2].1, 0);
+    assert!((r[2].0 - (8478.5 + 21.4 / 24.0)).abs() < 0.01);
+
+    // 2023年水星三次逆行，有逆行退回前一星座的情况
+    let r = ingresses(1, 12, |i, _| (i as f64) * std::f64::consts::PI / 6.0, t0, t0 + 365.0);
+    assert!(r.iter().any(|x| !x.2));
+
+    // 月亮一个月经过二十八宿
+    let r = ingresses(10, 28, crate::internal::xiu::xiu_lon, t0, t0 + 27.3);
+    assert!(r.len() >= 27);
+}
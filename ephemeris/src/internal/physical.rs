@@ -0,0 +1,305 @@
+use std::f64::consts::PI;
+use crate::internal::{ constants, ephemeris, lunnar, math_utils };
+
+// 月亮半径与地球赤道半径之比
+const MOON_K: f64 = 0.272481;
+// 地球赤道半径，千米
+const EARTH_R: f64 = 6378.14;
+// 月亮赤道面与黄道的交角
+const MOON_I: f64 = 1.54242 * (PI / 180.0);
+
+// 两点的角距
+fn separation(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let c = a.1.sin() * b.1.sin() + a.1.cos() * b.1.cos() * (a.0 - b.0).cos();
+    c.clamp(-1.0, 1.0).acos()
+}
+
+// 位置角，从天北极向东量，b相对a
+fn position_angle(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let y = b.1.cos() * (b.0 - a.0).sin();
+    let x = b.1.sin() * a.1.cos() - b.1.cos() * a.1.sin() * (b.0 - a.0).cos();
+    math_utils::rad2mrad(y.atan2(x))
+}
+
+/// 月面物理数据
+///
+/// 返回(被照亮比例, 相位角, 亮边缘位置角, 月龄(日), 视直径, 经天平动, 纬天平动)，角度为弧度制。
+/// 地心视位置，天平动只计光学天平动
+/// # Argument
+/// - `jd`: 力学时，J2000起算的日数
+pub fn moon_physical(jd: f64) -> (f64, f64, f64, f64, f64, f64, f64) {
+    let m = ephemeris::compute_position(10, jd, 0.0, 0.0);
+    let s = ephemeris::compute_position(9, jd, 0.0, 0.0);
+    let (lon, lat, dist) = (m.2, m.3, m.7); // 距离单位千米
+    let r = s.7 * 149597870.7;
+
+    // 距角和相位角(Meeus 48.2、48.3)
+    let psi = separation((lon, lat), (s.2, s.3));
+    let i = (r * psi.sin()).atan2(dist - r * psi.cos());
+    let k = (1.0 + i.cos()) / 2.0;
+    // 亮边缘中点的位置角，即太阳方向的位置角
+    let chi = position_angle((m.4, m.5), (s.4, s.5));
+
+    // 月龄，由东八区时间的上一次朔起算
+    let bj = jd - math_utils::dt_t(jd) + 8.0 / 24.0;
+    let age = bj - lunnar::moon_phase_accurate(lunnar::lunation_number(bj), 0);
+
+    let d = 2.0 * ((MOON_K * EARTH_R) / dist).asin();
+
+    // 光学天平动(Meeus 53)
+    let t = jd / 36525.0;
+    let f = (93.272095 + 483202.0175233 * t - 0.0036539 * t * t).to_radians();
+    let node = (125.0445479 - 1934.1362891 * t + 0.0020754 * t * t).to_radians();
+    let w = lon - ephemeris::nutation_lon2(t) - node;
+    let a = (w.sin() * lat.cos() * MOON_I.cos() - lat.sin() * MOON_I.sin()).atan2(w.cos() * lat.cos());
+    let l = math_utils::rad2rrad(a - f);
+    let b = (-w.sin() * lat.cos() * MOON_I.sin() - lat.sin() * MOON_I.cos()).asin();
+
+    (k, i, chi, age, d, l, b)
+}
+
+// 行星赤道半径，千米，水星到冥王星
+const PLANET_R: [f64; 8] = [2439.7, 6051.8, 3396.2, 71492.0, 60268.0, 25559.0, 24764.0, 1188.3];
+// 天文单位，千米
+const AU: f64 = 149597870.7;
+// 光行1天文单位的时间，日
+const LIGHT_TIME: f64 = 0.0057755183;
+
+/// 土星光环
+///
+/// 返回(光环对地球的倾角B, 对太阳的倾角B', 从土星看日地的经度差ΔU, 土星日心黄经l, 日心黄纬b, 地心黄经λ, 地心黄纬β, 光环升交点Ω)，
+/// 光环倾角北面为正(Meeus 45)
+/// # Argument
+/// - `jd`: 力学时，J2000起算的日数
+pub fn saturn_ring(jd: f64) -> (f64, f64, f64, f64, f64, f64, f64, f64) {
+    let t = jd / 36525.0;
+    let inc = (28.075216 - 0.012998 * t + 0.000004 * t * t).to_radians();
+    let node = (169.50847 + 1.394681 * t + 0.000412 * t * t).to_radians();
+    // 地心几何位置，计光行时
+    let ((lon, lat, dist), _) = ephemeris::reduced_position(5, jd, 1, 1, 0.0, 0.0, 0.0);
+    // 光行时之前的日心位置，并改正从土星看太阳的光行差
+    let ((l, b, r), _) = ephemeris::reduced_position(5, jd - dist * LIGHT_TIME, 0, 0, 0.0, 0.0, 0.0);
+    let l = l - (0.01759_f64).to_radians() / r;
+    let b = b - ((0.000764_f64).to_radians() * (l - node).cos()) / r;
+
+    let tilt = |x: f64, y: f64| (inc.sin() * y.cos() * (x - node).sin() - inc.cos() * y.sin()).asin();
+    let u = |x: f64, y: f64|
+        (inc.sin() * y.sin() + inc.cos() * y.cos() * (x - node).sin()).atan2(y.cos() * (x - node).cos());
+    let du = math_utils::rad2rrad(u(l, b) - u(lon, lat)).abs();
+    (tilt(lon, lat), tilt(l, b), du, l, b, lon, lat, node)
+}
+
+/// 行星视面数据
+///
+/// 返回(星等, 距角, 是否在太阳以东, 相位角, 被照亮比例, 赤道视直径)，角度为弧度制。
+/// 星等取《天文年历》(1984)的公式(Meeus 41)，土星计入光环
+/// # Argument
+/// - `xt`: 1-8，水星到冥王星
+/// - `jd`: 力学时，J2000起算的日数
+pub fn planet_physical(xt: usize, jd: f64) -> (f64, f64, bool, f64, f64, f64) {
+    assert!((1..=8).contains(&xt), "not a planet {}", xt);
+    let p = ephemeris::compute_position(xt, jd, 0.0, 0.0);
+    let s = ephemeris::compute_position(9, jd, 0.0, 0.0);
+    let (r, d, big_r) = (p.6, p.7, s.7);
+
+    let elongation = separation((p.2, p.3), (s.2, s.3));
+    let east = math_utils::rad2mrad(p.2 - s.2) < PI;
+    let cos_i = ((r * r + d * d - big_r * big_r) / (2.0 * r * d)).clamp(-1.0, 1.0);
+    let i = cos_i.acos();
+    let k = (1.0 + cos_i) / 2.0;
+    let diameter = 2.0 * (PLANET_R[xt - 1] / (d * AU)).asin();
+
+    let x = i.to_degrees();
+    let base = 5.0 * (r * d).log10();
+    let mag = match xt {
+        1 => -0.42 + base + 0.038 * x - 0.000273 * x * x + 0.000002 * x * x * x,
+        2 => -4.4 + base + 0.0009 * x + 0.000239 * x * x - 0.00000065 * x * x * x,
+        3 => -1.52 + base + 0.016 * x,
+        4 => -9.4 + base + 0.005 * x,
+        5 => {
+            let (b, _, du, ..) = saturn_ring(jd);
+            let sb = b.abs().sin();
+            -8.88 + base + 0.044 * du.to_degrees() - 2.6 * sb + 1.25 * sb * sb
+        }
+        6 => -7.19 + base,
+        7 => -6.87 + base,
+        _ => -1.0 + base,
+    };
+    (mag, elongation, east, i, k, diameter)
+}
+
+/// 太阳自转参数
+///
+/// 返回(自转轴位置角P, 日面中心日面纬度B0, 日面中心日面经度L0, 卡林顿自转周数)，角度为弧度制(Meeus 29)。
+/// 卡林顿自转周数的小数部分由L0得到，L0每周从360度减到0度
+/// # Argument
+/// - `jd`: 力学时，J2000起算的日数
+pub fn sun_physical(jd: f64) -> (f64, f64, f64, f64) {
+    let t = jd / 36525.0;
+    let jde = jd + constants::J2000;
+    let theta = ((jde - 2398220.0) * 360.0 / 25.38).to_radians();
+    let inc = 7.25_f64.to_radians();
+    let k = (73.6667 + (1.3958333 * (jde - 2396758.0)) / 36525.0).to_radians();
+
+    // 视黄经，lam计章动，lam0不计章动
+    let lam = ephemeris::compute_position(9, jd, 0.0, 0.0).2;
+    let lam0 = lam - ephemeris::nutation_lon2(t);
+    let e = ephemeris::true_sidereal(jd).1;
+
+    let x = (-lam.cos() * e.tan()).atan();
+    let y = (-(lam0 - k).cos() * inc.tan()).atan();
+    let p = x + y;
+    let b0 = ((lam0 - k).sin() * inc.sin()).asin();
+    let eta = (-(lam0 - k).sin() * inc.cos()).atan2(-(lam0 - k).cos());
+    let l0 = math_utils::rad2mrad(eta - theta);
+
+    // 第C周开始于JDE=2398140.2270+27.2752316C，周期项不足0.2日
+    let c = (jde - 2398140.227) / 27.2752316;
+    let f = 1.0 - l0 / (2.0 * PI);
+    let n = (c - f).round();
+    (p, b0, l0, n + f)
+}
+
+// 自转模型(IAU)：北极J2000赤经、赤纬及每儒略世纪的变化(度)，本初子午线J2000时的值及每日自转(度)
+const MARS_POLE: (f64, f64, f64, f64) = (317.68143, -0.1061, 52.8865, -0.0609);
+const JUPITER_POLE: (f64, f64, f64, f64) = (268.056595, -0.006499, 64.495303, 0.002413);
+const MARS_W: (f64, f64) = (176.63, 350.89198226);
+// 木星第一、二、三系统
+const JUPITER_W: [(f64, f64); 3] = [(67.1, 877.9), (43.3, 870.27), (284.95, 870.536)];
+
+// 行星北极的J2000赤经、赤纬
+fn pole(p: (f64, f64, f64, f64), t: f64) -> (f64, f64) {
+    ((p.0 + p.1 * t).to_radians(), (p.2 + p.3 * t).to_radians())
+}
+
+/// 行星视面方位
+///
+/// 返回(中央经线经度, 地心方向的行星面纬度DE, 自转轴位置角P)，弧度制。
+/// 中央经线为西经，随时间增加；位置角相对J2000赤道；计光行时，不计相位改正
+/// # Argument
+/// - `xt`: 3:火星， 4:木星
+/// - `system`: 木星经度系统，0-2对应第一、二、三系统，其余行星忽略
+/// - `jd`: 力学时，J2000起算的日数
+pub fn disk_orientation(xt: usize, system: usize, jd: f64) -> (f64, f64, f64) {
+    let (d, tau) = ephemeris::astrometric_j2000(xt, jd);
+    let (pole_p, w) = match xt {
+        3 => (MARS_POLE, MARS_W),
+        4 => (JUPITER_POLE, JUPITER_W[system]),
+        _ => panic!("no rotation model for {}", xt),
+    };
+    let jd = jd - tau;
+    let (a0, d0) = pole(pole_p, jd / 36525.0);
+    let w = (w.0 + w.1 * jd).to_radians();
+
+    // 行星指向地球的单位矢量
+    let r = (d.0 * d.0 + d.1 * d.1 + d.2 * d.2).sqrt();
+    let u = (-d.0 / r, -d.1 / r, -d.2 / r);
+    let p = (d0.cos() * a0.cos(), d0.cos() * a0.sin(), d0.sin());
+    let q = (-a0.sin(), a0.cos(), 0.0); // 行星赤道在地球赤道上的升交点
+    let n = (p.1 * q.2 - p.2 * q.1, p.2 * q.0 - p.0 * q.2, p.0 * q.1 - p.1 * q.0);
+    let dot = |a: (f64, f64, f64), b: (f64, f64, f64)| a.0 * b.0 + a.1 * b.1 + a.2 * b.2;
+
+    let cm = math_utils::rad2mrad(w - dot(u, n).atan2(dot(u, q)));
+    let de = dot(u, p).asin();
+    let (ra, dec) = (d.1.atan2(d.0), (d.2 / r).asin());
+    let pa = position_angle((ra, dec), (a0, d0));
+    (cm, de, pa)
+}
+
+/// 土星光环的视面数据
+///
+/// 返回(光环对地球的倾角B, 对太阳的倾角B', 光环北极位置角P, 光环外缘视长轴, 视短轴)，弧度制(Meeus 45)
+/// # Argument
+/// - `jd`: 力学时，J2000起算的日数
+pub fn saturn_ring_disk(jd: f64) -> (f64, f64, f64, f64, f64) {
+    let t = jd / 36525.0;
+    let (b, b1, _, _, _, lon, lat, node) = saturn_ring(jd);
+    let inc = (28.075216 - 0.012998 * t + 0.000004 * t * t).to_radians();
+    let dist = ephemeris::reduced_position(5, jd, 1, 1, 0.0, 0.0, 0.0).0.2;
+
+    // 光环北极，及改正光行差、章动后的土星位置
+    let d_l = ephemeris::nutation_lon2(t);
+    let sun = ephemeris::reduced_position(9, jd, 1, 0, 0.0, 0.0, 0.0).0.0;
+    let k = 0.005693_f64.to_radians();
+    let lon1 = lon + (k * (sun - lon).cos()) / lat.cos() + d_l;
+    let lat1 = lat + k * (sun - lon).sin() * lat.sin();
+    let e = ephemeris::true_sidereal(jd).1;
+    let q = math_utils::llr_conv((node - PI / 2.0 + d_l, PI / 2.0 - inc, 1.0), e);
+    let z = math_utils::llr_conv((lon1, lat1, 1.0), e);
+    let p = position_angle((z.0, z.1), (q.0, q.1));
+
+    let a = (375.35 / dist / 3600.0).to_radians();
+    (b, b1, p, a, a * b.abs().sin())
+}
+
+#[test]
+fn test_moon_physical() {
+    // Meeus 例48.a：1992-04-12 0h TD，k=0.6786，亮边缘位置角285.0度
+    let jd = -2820.5;
+    let (k, _, chi, _, _, l, b) = moon_physical(jd);
+    assert!((k - 0.6786).abs() < 0.001, "{}", k);
+    assert!((chi.to_degrees() - 285.0).abs() < 0.1, "{}", chi.to_degrees());
+    // Meeus 例53.a：经天平动-1.206度，纬天平动4.194度
+    assert!((l.to_degrees() + 1.206).abs() < 0.01, "{}", l.to_degrees());
+    assert!((b.to_degrees() - 4.194).abs() < 0.01, "{}", b.to_degrees());
+
+    // 新月前后月龄接近0或29.5天，被照亮比例接近0
+    let k0 = lunnar::lunation_number(8631.0);
+    let t = lunnar::moon_phase_accurate(k0, 0) - 8.0 / 24.0 + math_utils::dt_t(8631.0) + 0.01;
+    let (k, i, _, age, d, _, _) = moon_physical(t);
+    assert!(age > 0.0 && age < 0.02, "{}", age);
+    assert!(k < 0.01 && i > 3.0);
+    assert!(d.to_degrees() > 0.48 && d.to_degrees() < 0.57);
+}
+
+#[test]
+fn test_planet_physical() {
+    // Meeus 例41.a：1992-12-20 0h TD金星，k=0.647
+    let (mag, e, east, _, k, _) = planet_physical(2, -2568.5);
+    assert!((k - 0.647).abs() < 0.002, "{}", k);
+    assert!(east && e.to_degrees() > 40.0);
+    assert!(mag < -4.0 && mag > -4.5, "{}", mag);
+
+    // Meeus 例45.a：1992-12-16 0h TD，B=16.442度，B'=14.679度，ΔU=4.198度
+    let (b, b1, du, ..) = saturn_ring(-2572.5);
+    assert!((b.to_degrees() - 16.442).abs() < 0.005, "{}", b.to_degrees());
+    assert!((b1.to_degrees() - 14.679).abs() < 0.005, "{}", b1.to_degrees());
+    assert!((du.to_degrees() - 4.198).abs() < 0.005, "{}", du.to_degrees());
+
+    // 木星冲日前后视直径约45-50角秒，星等约-2.9
+    let (mag, e, _, _, k, d) = planet_physical(4, 8709.0);
+    assert!(e.to_degrees() > 170.0 && k > 0.999);
+    assert!((d.to_degrees() * 3600.0 - 49.0).abs() < 2.0, "{}", d.to_degrees() * 3600.0);
+    assert!((mag + 2.9).abs() < 0.1, "{}", mag);
+}
+
+#[test]
+fn test_disk() {
+    // Meeus 例29.a：1992-10-13 0h TD，P=26.27度，B0=5.99度，L0=238.63度
+    let (p, b0, l0, c) = sun_physical(-2636.5);
+    assert!((p.to_degrees() - 26.27).abs() < 0.01, "{}", p.to_degrees());
+    assert!((b0.to_degrees() - 5.99).abs() < 0.01, "{}", b0.to_degrees());
+    assert!((l0.to_degrees() - 238.63).abs() < 0.02, "{}", l0.to_degrees());
+    assert_eq!(c.floor(), 1861.0);
+
+    // Meeus 例45.a：1992-12-16 0h TD，P=6.741度，长轴35.87角秒，短轴10.15角秒
+    let (_, _, p, a, b) = saturn_ring_disk(-2572.5);
+    assert!((p.to_degrees() - 6.741).abs() < 0.005, "{}", p.to_degrees());
+    assert!((a.to_degrees() * 3600.0 - 35.87).abs() < 0.02);
+    assert!((b.to_degrees() * 3600.0 - 10.15).abs() < 0.02);
+
+    // Meeus 例43.a：1992-12-16 0h UT，木星第一、二系统中央经线268.06、72.74度，DE=-2.48度，P=24.80度
+    let jd = -2572.5 + 59.0 / 86400.0;
+    let (w1, de, pa) = disk_orientation(4, 0, jd);
+    let (w2, ..) = disk_orientation(4, 1, jd);
+    assert!((w1.to_degrees() - 268.06).abs() < 0.5, "{}", w1.to_degrees());
+    assert!((w2.to_degrees() - 72.74).abs() < 0.5, "{}", w2.to_degrees());
+    assert!((de.to_degrees() + 2.48).abs() < 0.05, "{}", de.to_degrees());
+    assert!((pa.to_degrees() - 24.8).abs() < 0.1, "{}", pa.to_degrees());
+
+    // Meeus 例42.a：1992-11-09 0h TD火星，DE=12.44度，P=347.64度，中央经线111.55度
+    let (cm, de, pa) = disk_orientation(3, 0, -2609.5);
+    assert!((cm.to_degrees() - 111.55).abs() < 0.5, "{}", cm.to_degrees());
+    assert!((de.to_degrees() - 12.44).abs() < 0.01, "{}", de.to_degrees());
+    assert!((pa.to_degrees() - 347.64).abs() < 0.1, "{}", pa.to_degrees());
+}
@@ -0,0 +1,175 @@
+use crate::internal::{ constants, math_utils };
+use crate::internal::ephemeris::{ compute_position_at, mqc2, Site, SEA_LEVEL };
+
+// 出没计算的采样步长（日）, 1小时内高度角不会两次穿越地平
+const STEP: f64 = 1.0 / 24.0;
+
+/// 天体视半径
+///
+/// 太阳和月亮计算视半径，行星视半径对出没影响很小，取0
+/// # Argument
+/// - `xt`: 星体序号
+/// - `dist`: 视距离, 月亮单位是千米，其余天体是天文单位
+pub fn semi_diameter(xt: usize, dist: f64) -> f64 {
+    match xt {
+        9 => 959.63 / constants::RAD / dist,
+        10 => (0.2725076 * constants::CS_R_EAR / dist).asin(),
+        _ => 0.0,
+    }
+}
+
+/// 站心真高度（不含大气折射）
+///
+/// 返回(高度角, 时角, 视半径)，时角在$-\pi-\pi$之间
+/// # Argument
+/// - `xt`: 星体序号
+/// - `t`: 力学时，J2000起算的日数
+/// - `l`: 经度，东经为正
+/// - `fa`: 纬度
+/// - `site`: 观测点海拔等参数
+pub fn topo_altitude(xt: usize, t: f64, l: f64, fa: f64, site: &Site) -> (f64, f64, f64) {
+    let (_, _, _, _, _, _, _, _, _, st_ra, st_dec, dist, _, _, (_, gst)) = compute_position_at(
+        xt,
+        t,
+        l,
+        fa,
+        site
+    );
+    let h = math_utils::rad2rrad(gst + l - st_ra); // 站心时角
+    let alt = (fa.sin() * st_dec.sin() + fa.cos() * st_dec.cos() * h.cos()).asin();
+    (alt, h, semi_diameter(xt, dist))
+}
+
+/// 求真高度穿越`h0`的时刻
+///
+/// 返回(升起时刻列表, 落下时刻列表, 起始时刻是否在`h0`之上)
+/// # Argument
+/// - `xt`: 星体序号
+/// - `t0`, `t1`: 力学时区间
+/// - `h0`: 目标真高度，传入视半径，返回目标高度
+pub fn altitude_crossings<F: Fn(f64) -> f64>(
+    xt: usize,
+    t0: f64,
+    t1: f64,
+    l: f64,
+    fa: f64,
+    site: &Site,
+    h0: F
+) -> (Vec<f64>, Vec<f64>, bool) {
+    let f = |t: f64| {
+        let (alt, _, sd) = topo_altitude(xt, t, l, fa, site);
+        alt - h0(sd)
+    };
+    let above = f(t0) > 0.0;
+    let roots = math_utils::find_roots(f, t0, t1, STEP);
+    let rise = roots
+        .iter()
+        .filter(|x| x.1)
+        .map(|x| x.0)
+        .collect();
+    let set = roots
+        .iter()
+        .filter(|x| !x.1)
+        .map(|x| x.0)
+        .collect();
+    (rise, set, above)
+}
+
+/// 中天时刻
+///
+/// 站心时角由负变正的时刻即上中天
+pub fn transits(xt: usize, t0: f64, t1: f64, l: f64, fa: f64, site: &Site) -> Vec<f64> {
+    let f = |t: f64| topo_altitude(xt, t, l, fa, site).1.sin();
+    math_utils::find_roots(f, t0, t1, STEP)
+        .into_iter()
+        .filter(|x| x.1)
+        .map(|x| x.0)
+        .collect()
+}
+
+/// 升、中天、落计算
+///
+/// 计算`t0`起一天内的升起、上中天、落下时刻, 以天体上边缘与视地平相切为出没，
+/// 太阳和月亮扣除视半径, 月亮站心坐标已经包含视差，
+/// 大气折射按`site`的比例缩放，高处观测的视地平再降低地平俯角
+/// 返回(升, 中天, 落, 起始时刻是否在地平上)
+pub fn rise_transit_set(
+    xt: usize,
+    t0: f64,
+    l: f64,
+    fa: f64,
+    site: &Site
+) -> (Option<f64>, Option<f64>, Option<f64>, bool) {
+    let t1 = t0 + 1.0;
+    let h0 = |sd: f64| mqc2(0.0) * site.refr - sd - site.dip;
+    let (rise, set, above) = altitude_crossings(xt, t0, t1, l, fa, site, h0);
+    let transit = transits(xt, t0, t1, l, fa, site);
+    (rise.first().copied(), transit.first().copied(), set.first().copied(), above)
+}
+
+/// 太阳高度事件
+///
+/// 计算`t0`起一天内太阳中心几何高度升到`h`以及降到`h`的时刻，不含大气折射，
+/// 用于晨昏蒙影、黄金时刻等计算
+/// 返回(上午穿越时刻, 下午穿越时刻, 起始时刻是否高于`h`)
+pub fn solar_altitude_events(
+    t0: f64,
+    l: f64,
+    fa: f64,
+    h: f64
+) -> (Option<f64>, Option<f64>, bool) {
+    let (rise, set, above) = altitude_crossings(9, t0, t0 + 1.0, l, fa, &SEA_LEVEL, |_| h);
+    (rise.first().copied(), set.first().copied(), above)
+}
+
+#[test]
+fn test_rise_transit_set() {
+    // 2023-7-23 北京 太阳 升05:04 中天12:20 落19:36（东八区）
+    let l = 116.383_f64.to_radians();
+    let fa = 39.9_f64.to_radians();
+    let t0 = 8603.5 - 8.0 / 24.0;
+    let t0 = t0 + math_utils::dt_t(t0);
+    let (r, m, s, _) = rise_transit_set(9, t0, l, fa, &SEA_LEVEL);
+    let local = |t: f64| (t - math_utils::dt_t(t) + 8.0 / 24.0 - 8603.5) * 24.0;
+    let (r, m, s) = (local(r.unwrap()), local(m.unwrap()), local(s.unwrap()));
+    println!("rise={} transit={} set={}", r, m, s);
+    assert!((r - (5.0 + 4.0 / 60.0)).abs() < 2.0 / 60.0);
+    assert!((m - (12.0 + 20.0 / 60.0)).abs() < 2.0 / 60.0);
+    assert!((s - (19.0 + 36.0 / 60.0)).abs() < 2.0 / 60.0);
+
+    // 海拔3000米，地平俯角约1.6度，日出提前、日落推迟
+    let site = Site { high: 3.0, refr: 0.7, dip: 1.6_f64.to_radians() };
+    let (r2, _, s2, _) = rise_transit_set(9, t0, l, fa, &site);
+    let (r2, s2) = (local(r2.unwrap()), local(s2.unwrap()));
+    assert!(r2 < r && s2 > s && (r - r2) * 60.0 > 5.0);
+}
+
+#[test]
+fn test_rise_set_polar() {
+    // 北纬80度夏至前后太阳全天在地平之上
+    let fa = 80.0_f64.to_radians();
+    let t0 = 8572.5;
+    let (r, _, s, above) = rise_transit_set(9, t0, 0.0, fa, &SEA_LEVEL);
+    assert!(r.is_none() && s.is_none() && above);
+    let (r, _, s, above) = rise_transit_set(9, t0 + 182.0, 0.0, fa, &SEA_LEVEL);
+    assert!(r.is_none() && s.is_none() && !above);
+}
+
+
+#[test]
+fn test_solar_altitude_events() {
+    // 2023-7-23 北京 民用晨光始04:33 天文昏影终21:30
+    let l = 116.383_f64.to_radians();
+    let fa = 39.9_f64.to_radians();
+    let t0 = 8603.5 - 8.0 / 24.0;
+    let t0 = t0 + math_utils::dt_t(t0);
+    let local = |t: f64| (t - math_utils::dt_t(t) + 8.0 / 24.0 - 8603.5) * 24.0;
+    let (m, _, _) = solar_altitude_events(t0, l, fa, -6.0_f64.to_radians());
+    assert!((local(m.unwrap()) - (4.0 + 33.0 / 60.0)).abs() < 1.0 / 60.0);
+    let (_, e, _) = solar_altitude_events(t0, l, fa, -18.0_f64.to_radians());
+    assert!((local(e.unwrap()) - (21.0 + 30.0 / 60.0)).abs() < 1.0 / 60.0);
+
+    // 北纬60度夏至，天文昏影整夜不终
+    let (m, e, above) = solar_altitude_events(8572.5, 0.0, 60.0_f64.to_radians(), -18.0_f64.to_radians());
+    assert!(m.is_none() && e.is_none() && above);
+}
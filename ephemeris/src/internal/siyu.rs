@@ -0,0 +1,129 @@
+use std::f64::consts::PI;
+use crate::internal::math_utils;
+use crate::internal::ephemeris::m_coord;
+
+// 地月引力常数 G(M地+M月)，千米^3/秒^2
+const GM_EARTH_MOON: f64 = 403503.2355;
+
+// 月亮平均轨道半长径(千米)及偏心率，用于平月孛距离
+const MOON_A: f64 = 383397.8;
+const MOON_E: f64 = 0.055545;
+
+// 紫炁28年一周天
+const ZIQI_PERIOD: f64 = 28.0 * 365.25;
+
+/// 平罗睺，月亮平升交点黄经(Meeus 47.7)
+/// # Argument
+/// - `t`: 力学时，J2000起算的儒略世纪数
+pub fn mean_node(t: f64) -> f64 {
+    let d = 125.0445479 - 1934.1362891 * t + 0.0020754 * t * t + (t * t * t) / 467441.0 -
+        (t * t * t * t) / 60616000.0;
+    math_utils::rad2mrad(d.to_radians())
+}
+
+/// 平月孛，月亮平远地点黄经，即平近地点加180度(Meeus 47.7)
+pub fn mean_apogee(t: f64) -> f64 {
+    let d = 83.3532465 + 4069.0137287 * t - 0.01032 * t * t - (t * t * t) / 80053.0 +
+        (t * t * t * t) / 18999000.0;
+    math_utils::rad2mrad(d.to_radians() + PI)
+}
+
+/// 紫炁，按28年一周天匀速顺行
+///
+/// 紫炁为虚星，没有对应的天体，历元由调用者给出
+/// # Argument
+/// - `t`: 力学时，J2000起算的儒略世纪数
+/// - `t0`: 历元，力学时，J2000起算的儒略世纪数
+/// - `lon0`: 历元时紫炁黄经
+pub fn ziqi(t: f64, t0: f64, lon0: f64) -> f64 {
+    math_utils::rad2mrad(lon0 + (((t - t0) * 36525.0) / ZIQI_PERIOD) * 2.0 * PI)
+}
+
+// 月亮地心黄道直角坐标(千米)及速度(千米/秒)
+fn moon_state(t: f64) -> ((f64, f64, f64), (f64, f64, f64)) {
+    let h = 0.01 / 36525.0;
+    let p = math_utils::llr2xyz(m_coord(t, -1, -1, -1));
+    let p0 = math_utils::llr2xyz(m_coord(t - h, -1, -1, -1));
+    let p1 = math_utils::llr2xyz(m_coord(t + h, -1, -1, -1));
+    let dt = 2.0 * 0.01 * 86400.0;
+    (p, ((p1.0 - p0.0) / dt, (p1.1 - p0.1) / dt, (p1.2 - p0.2) / dt))
+}
+
+fn cross(a: (f64, f64, f64), b: (f64, f64, f64)) -> (f64, f64, f64) {
+    (a.1 * b.2 - a.2 * b.1, a.2 * b.0 - a.0 * b.2, a.0 * b.1 - a.1 * b.0)
+}
+
+/// 真罗睺，月亮瞬时轨道(密切轨道)升交点黄经
+pub fn true_node(t: f64) -> f64 {
+    let (p, v) = moon_state(t);
+    let h = cross(p, v);
+    math_utils::rad2mrad(h.0.atan2(-h.1))
+}
+
+/// 真月孛，月亮瞬时轨道(密切轨道)远地点
+///
+/// 返回(黄经, 黄纬, 地心距千米)
+pub fn true_apogee(t: f64) -> (f64, f64, f64) {
+    let (p, v) = moon_state(t);
+    let h = cross(p, v);
+    let r = (p.0 * p.0 + p.1 * p.1 + p.2 * p.2).sqrt();
+    let v2 = v.0 * v.0 + v.1 * v.1 + v.2 * v.2;
+    let w = cross(v, h);
+    // 偏心率矢量指向近地点
+    let e = (
+        w.0 / GM_EARTH_MOON - p.0 / r,
+        w.1 / GM_EARTH_MOON - p.1 / r,
+        w.2 / GM_EARTH_MOON - p.2 / r,
+    );
+    let ecc = (e.0 * e.0 + e.1 * e.1 + e.2 * e.2).sqrt();
+    let a = 1.0 / (2.0 / r - v2 / GM_EARTH_MOON);
+    let (lon, lat, _) = math_utils::xyz2llr((-e.0, -e.1, -e.2));
+    (lon, lat, a * (1.0 + ecc))
+}
+
+/// 四余黄道坐标
+///
+/// 返回(黄经, 黄纬, 地心距千米)，黄经为瞬时平黄道坐标(不含章动)，罗睺、计都距离取0
+/// # Argument
+/// - `xt`: 11:平罗睺， 12:真罗睺， 13:平计都， 14:真计都， 15:平月孛， 16:真月孛
+/// - `t`: 力学时，J2000起算的儒略世纪数
+pub fn siyu_coord(xt: usize, t: f64) -> (f64, f64, f64) {
+    match xt {
+        11 => (mean_node(t), 0.0, 0.0),
+        12 => (true_node(t), 0.0, 0.0),
+        13 => (math_utils::rad2mrad(mean_node(t) + PI), 0.0, 0.0),
+        14 => (math_utils::rad2mrad(true_node(t) + PI), 0.0, 0.0),
+        15 => (mean_apogee(t), 0.0, MOON_A * (1.0 + MOON_E)),
+        16 => true_apogee(t),
+        _ => panic!("invalid siyu index {}", xt),
+    }
+}
+
+#[test]
+fn test_siyu_coord() {
+    // 1992-04-12 0h TD 平升交点274.4007度
+    let t = -0.077221081451;
+    assert!((mean_node(t).to_degrees() - 274.400656).abs() < 1e-4);
+
+    // 真罗睺在平罗睺附近摆动，幅度不超过2度
+    for i in 0..30 {
+        let t = (i as f64) * 1.3 / 36525.0;
+        let d = math_utils::rad2rrad(true_node(t) - mean_node(t)).to_degrees();
+        assert!(d.abs() < 2.0);
+    }
+
+    // 真月孛与平月孛相差可达30度，距离在40万千米附近
+    let (lon, _, r) = true_apogee(0.0);
+    assert!(math_utils::rad2rrad(lon - mean_apogee(0.0)).abs() < 35.0_f64.to_radians());
+    assert!((r - 405000.0).abs() < 10000.0);
+
+    // 计都与罗睺相对
+    let d = math_utils::rad2rrad(siyu_coord(13, t).0 - siyu_coord(11, t).0);
+    assert!((d.abs() - PI).abs() < 1e-9);
+
+    // 紫炁28年一周天，7年行一象限
+    let t0 = 0.3;
+    assert!((ziqi(t0, t0, 1.0) - 1.0).abs() < 1e-12);
+    assert!((ziqi(t0 + 28.0 * 365.25 / 36525.0, t0, 1.0) - 1.0).abs() < 1e-9);
+    assert!((ziqi(t0 + 7.0 * 365.25 / 36525.0, t0, 1.0) - 1.0 - PI / 2.0).abs() < 1e-9);
+}
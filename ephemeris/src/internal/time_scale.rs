@@ -0,0 +1,177 @@
+use std::sync::RwLock;
+use lazy_static::lazy_static;
+use crate::internal::{ constants, math_utils };
+use crate::internal::lunnar::JulianDate;
+
+// TT-TAI，秒
+const TT_TAI: f64 = 32.184;
+
+// 内置闰秒表：生效日期(年、月，当月1日0h UTC)，TAI-UTC(秒)
+const LEAP_SECONDS: [(i32, i32, f64); 28] = [
+    (1972, 1, 10.0),
+    (1972, 7, 11.0),
+    (1973, 1, 12.0),
+    (1974, 1, 13.0),
+    (1975, 1, 14.0),
+    (1976, 1, 15.0),
+    (1977, 1, 16.0),
+    (1978, 1, 17.0),
+    (1979, 1, 18.0),
+    (1980, 1, 19.0),
+    (1981, 7, 20.0),
+    (1982, 7, 21.0),
+    (1983, 7, 22.0),
+    (1985, 7, 23.0),
+    (1988, 1, 24.0),
+    (1990, 1, 25.0),
+    (1991, 1, 26.0),
+    (1992, 7, 27.0),
+    (1993, 7, 28.0),
+    (1994, 7, 29.0),
+    (1996, 1, 30.0),
+    (1997, 7, 31.0),
+    (1999, 1, 32.0),
+    (2006, 1, 33.0),
+    (2009, 1, 34.0),
+    (2012, 7, 35.0),
+    (2015, 7, 36.0),
+    (2017, 1, 37.0),
+];
+
+lazy_static! {
+    // 当前闰秒表
+    static ref LEAPS: RwLock<Vec<(i32, i32, f64)>> = RwLock::new(LEAP_SECONDS.to_vec());
+}
+
+// 生效日期，UTC，J2000起算的日数
+fn leap_start(y: i32, m: i32) -> f64 {
+    JulianDate::from_day(y, m, 1.0).jd - constants::J2000
+}
+
+/// 设置闰秒表，`table`为(年, 月, TAI-UTC秒)，按日期排序，为空时不做改变
+pub fn set_leap_seconds(mut table: Vec<(i32, i32, f64)>) -> bool {
+    if table.is_empty() {
+        return false;
+    }
+    table.sort_by_key(|x| x.0 * 12 + x.1);
+    *LEAPS.write().unwrap() = table;
+    true
+}
+
+/// 当前闰秒表
+pub fn leap_seconds() -> Vec<(i32, i32, f64)> {
+    LEAPS.read().unwrap().clone()
+}
+
+/// 在表末增加一个正闰秒，于`y`年`m`月1日0h UTC生效，日期须晚于表中最后一项
+pub fn add_leap_second(y: i32, m: i32) -> bool {
+    let mut t = LEAPS.write().unwrap();
+    let v = match t.last() {
+        Some(last) if last.0 * 12 + last.1 >= y * 12 + m => {
+            return false;
+        }
+        Some(last) => last.2 + 1.0,
+        None => 10.0,
+    };
+    t.push((y, m, v));
+    true
+}
+
+/// TAI-UTC，单位秒，早于闰秒表时返回`None`
+///
+/// # Argument
+/// - `utc`: UTC，J2000起算的日数
+pub fn tai_utc(utc: f64) -> Option<f64> {
+    let t = LEAPS.read().unwrap();
+    t.iter()
+        .rev()
+        .find(|x| utc >= leap_start(x.0, x.1))
+        .map(|x| x.2)
+}
+
+// TAI转UTC，早于闰秒表时返回None，生效时刻留10微秒余量避免舍入误差
+fn tai2utc(tai: f64) -> Option<f64> {
+    let t = LEAPS.read().unwrap();
+    t.iter()
+        .rev()
+        .map(|x| (tai - x.2 / 86400.0, leap_start(x.0, x.1)))
+        .find(|x| x.0 >= x.1 - 1e-10)
+        .map(|x| x.0)
+}
+
+/// TDB-TT，单位秒
+///
+/// 取周期项的主要部分(USNO Circular 179)，误差约10微秒
+/// # Argument
+/// - `tt`: 力学时，J2000起算的日数
+pub fn tdb_tt(tt: f64) -> f64 {
+    let t = tt / 36525.0;
+    0.001657 * (628.3076 * t + 6.2401).sin() +
+        0.000022 * (575.3385 * t + 4.297).sin() +
+        0.000014 * (1256.6152 * t + 6.1969).sin() +
+        0.000005 * (606.9777 * t + 4.0212).sin() +
+        0.000005 * (52.9691 * t + 0.4444).sin() +
+        0.000002 * (21.3299 * t + 5.5431).sin() +
+        0.00001 * t * (628.3076 * t + 4.249).sin()
+}
+
+/// 转为力学时TT
+///
+/// 闰秒表以前的UTC按UT1处理，UT1与TT之差取当前ΔT模型
+/// # Argument
+/// - `t`: J2000起算的日数
+/// - `scale`: 0:UTC， 1:UT1， 2:TAI， 3:TT， 4:TDB
+pub fn to_tt(t: f64, scale: usize) -> f64 {
+    match scale {
+        0 =>
+            match tai_utc(t) {
+                Some(dt) => t + (dt + TT_TAI) / 86400.0,
+                None => to_tt(t, 1),
+            }
+        1 => t + math_utils::dt_t(t),
+        2 => t + TT_TAI / 86400.0,
+        3 => t,
+        4 => t - tdb_tt(t) / 86400.0,
+        _ => panic!("invalid time scale {}", scale),
+    }
+}
+
+/// 由力学时TT转为其他时间尺度，参数同[`to_tt`]
+pub fn from_tt(tt: f64, scale: usize) -> f64 {
+    match scale {
+        0 => tai2utc(tt - TT_TAI / 86400.0).unwrap_or_else(|| from_tt(tt, 1)),
+        1 => {
+            // ΔT随时间变化缓慢，迭代一次即可
+            let ut = tt - math_utils::dt_t(tt);
+            tt - math_utils::dt_t(ut)
+        }
+        2 => tt - TT_TAI / 86400.0,
+        3 => tt,
+        4 => tt + tdb_tt(tt) / 86400.0,
+        _ => panic!("invalid time scale {}", scale),
+    }
+}
+
+#[test]
+fn test_time_scale() {
+    // 2017年1月1日0h UTC起TT-UTC为69.184秒，此前1秒为68.184秒
+    let t = leap_start(2017, 1);
+    assert!(((to_tt(t, 0) - t) * 86400.0 - 69.184).abs() < 1e-6);
+    let t1 = t - 1.0 / 86400.0;
+    assert!(((to_tt(t1, 0) - t1) * 86400.0 - 68.184).abs() < 1e-6);
+    assert!((from_tt(to_tt(t, 0), 0) - t).abs() * 86400.0 < 1e-5);
+
+    // 各尺度往返一致
+    for scale in 0..5 {
+        for t in [-10000.0, 100.5, 6209.3, 20000.0] {
+            let x = from_tt(to_tt(t, scale), scale);
+            assert!((x - t).abs() * 86400.0 < 1e-5, "scale {} t {}", scale, t);
+        }
+    }
+
+    // 1972年以前UTC按UT1处理
+    assert_eq!(to_tt(-12000.0, 0), to_tt(-12000.0, 1));
+
+    // TDB-TT不超过1.7毫秒
+    assert!((0..3650).all(|d| tdb_tt(d as f64).abs() < 0.0017));
+}
@@ -0,0 +1,75 @@
+use crate::internal::{ constants, math_utils };
+
+// 二十八宿距星J2000赤经(时、分、秒)、赤纬(度、分、秒)，从角宿开始
+// 参宿距星按《仪象考成》取参宿一(猎户座ζ)，取参宿三时现代觜宿宽度为负
+const STARS: [(f64, f64, f64, f64, f64, f64); 28] = [
+    (13.0, 25.0, 11.58, -11.0, 9.0, 40.8), // 角宿一 室女座α
+    (14.0, 12.0, 53.7, -10.0, 16.0, 25.0), // 亢宿一 室女座κ
+    (14.0, 50.0, 52.7, -16.0, 2.0, 30.0), // 氐宿一 天秤座α2
+    (15.0, 58.0, 51.1, -26.0, 6.0, 51.0), // 房宿一 天蝎座π
+    (16.0, 21.0, 11.3, -25.0, 35.0, 34.0), // 心宿一 天蝎座σ
+    (16.0, 51.0, 52.2, -38.0, 2.0, 51.0), // 尾宿一 天蝎座μ1
+    (18.0, 5.0, 48.5, -30.0, 25.0, 27.0), // 箕宿一 人马座γ
+    (18.0, 45.0, 39.4, -26.0, 59.0, 27.0), // 斗宿一 人马座φ
+    (20.0, 21.0, 0.7, -14.0, 46.0, 53.0), // 牛宿一 摩羯座β
+    (20.0, 47.0, 40.6, -9.0, 29.0, 45.0), // 女宿一 宝瓶座ε
+    (21.0, 31.0, 33.5, -5.0, 34.0, 16.0), // 虚宿一 宝瓶座β
+    (22.0, 5.0, 47.0, 0.0, -19.0, 11.0), // 危宿一 宝瓶座α
+    (23.0, 4.0, 45.7, 15.0, 12.0, 19.0), // 室宿一 飞马座α
+    (0.0, 13.0, 14.2, 15.0, 11.0, 1.0), // 壁宿一 飞马座γ
+    (0.0, 47.0, 20.3, 24.0, 16.0, 2.0), // 奎宿一 仙女座ζ
+    (1.0, 54.0, 38.4, 20.0, 48.0, 29.0), // 娄宿一 白羊座β
+    (2.0, 43.0, 27.1, 27.0, 42.0, 26.0), // 胃宿一 白羊座35
+    (3.0, 44.0, 52.5, 24.0, 6.0, 48.0), // 昴宿一 金牛座17
+    (4.0, 28.0, 37.0, 19.0, 10.0, 50.0), // 毕宿一 金牛座ε
+    (5.0, 35.0, 8.3, 9.0, 56.0, 3.0), // 觜宿一 猎户座λ
+    (5.0, 40.0, 45.5, -1.0, 56.0, 34.0), // 参宿一 猎户座ζ
+    (6.0, 22.0, 57.6, 22.0, 30.0, 49.0), // 井宿一 双子座μ
+    (8.0, 31.0, 35.7, 18.0, 5.0, 40.0), // 鬼宿一 巨蟹座θ
+    (8.0, 37.0, 39.4, 5.0, 42.0, 14.0), // 柳宿一 长蛇座δ
+    (9.0, 27.0, 35.2, -8.0, 39.0, 31.0), // 星宿一 长蛇座α
+    (9.0, 51.0, 28.7, -14.0, 50.0, 48.0), // 张宿一 长蛇座υ1
+    (10.0, 59.0, 46.5, -18.0, 17.0, 56.0), // 翼宿一 巨爵座α
+    (12.0, 15.0, 48.4, -17.0, 32.0, 31.0), // 轸宿一 乌鸦座γ
+];
+
+// J2000平黄赤交角
+const E2000: f64 = 84381.406 / constants::RAD;
+
+// 距星J2000黄经
+fn lon_j2000(i: usize) -> f64 {
+    let (h, m, s, d, dm, ds) = STARS[i];
+    let ra = ((h + m / 60.0 + s / 3600.0) * 15.0).to_radians();
+    // 赤纬的符号由第一个非零项决定
+    let sign = if d < 0.0 || (d == 0.0 && dm < 0.0) { -1.0 } else { 1.0 };
+    let dec = (sign * (d.abs() + dm.abs() / 60.0 + ds / 3600.0)).to_radians();
+    math_utils::llr_conv((ra, dec, 1.0), -E2000).0
+}
+
+/// 二十八宿起点(距星)的黄经
+///
+/// 距星J2000黄经加黄经总岁差，不计自行，黄道宿度
+/// # Argument
+/// - `i`: 0-27，从角宿开始
+/// - `t`: 力学时，J2000起算的儒略世纪数
+pub fn xiu_lon(i: usize, t: f64) -> f64 {
+    let p = (5028.796195 * t + 1.1054348 * t * t) / constants::RAD;
+    math_utils::rad2mrad(lon_j2000(i) + p)
+}
+
+#[test]
+fn test_xiu() {
+    // 各宿按顺序排列，宿度之和为一周，觜宿约1度
+    let mut sum = 0.0;
+    for i in 0..28 {
+        let w = math_utils::rad2mrad(xiu_lon((i + 1) % 28, 0.0) - xiu_lon(i, 0.0));
+        assert!(w < 40.0_f64.to_radians(), "{}", i);
+        sum += w;
+    }
+    assert!((sum - 2.0 * std::f64::consts::PI).abs() < 1e-9);
+    let w = xiu_lon(20, 0.0) - xiu_lon(19, 0.0);
+    assert!(w > 0.0 && w < 2.0_f64.to_radians());
+
+    // 角宿一J2000黄经203度50分
+    assert!((xiu_lon(0, 0.0).to_degrees() - 203.84).abs() < 0.01);
+}
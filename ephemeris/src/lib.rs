@@ -1,6 +1,10 @@
 mod  internal;
 pub mod lunnar;
 pub mod astronomy;
+pub mod eclipse;
+pub mod aspects;
+pub mod time;
+pub mod physical;
 
 
 /// 暴露AstroyDate等结构调用
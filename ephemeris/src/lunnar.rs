@@ -1,6 +1,7 @@
 /// 农历农历相关的函数
 use crate::internal::lunnar::{ JulianDate, calc_year_calendar, self };
 use crate::internal::constants;
+use crate::astronomy;
 
 /// 年历相关结构
 /// 
@@ -324,7 +325,7 @@ impl MonthCalender {
 /// x.0, if x.3!=0{ "闰"} else {""}, YM[(x.1 as usize + 11)%12], x.2 );
 /// ```
 /// - 公历转四柱八字
-/// **注意**: 默认是采用东八区计算的，自己可以转真太阳时
+/// **注意**: 默认是采用东八区计算的，按出生地真太阳时排盘使用[`sizhu_with_lon`](SolorDate::sizhu_with_lon)
 /// ```
 ///use rust_ephemeris::lunnar::*;
 /// let d = SolorDate(2023, 11, 11);
@@ -388,9 +389,30 @@ impl SolorDate {
 
     /// 计算年月日干支 采用浮点12 点为 0.5
     /// 
-    /// 计算四柱，按照东八区时间推算，可以自行转为太阳时
+    /// 计算四柱，按照东八区时间推算，需要按真太阳时排盘时使用[`sizhu_with_lon`](SolorDate::sizhu_with_lon)
     /// 用法参见[`SolorDate`](crate::lunnar::SolorDate)
     pub fn sizhu(&self, t: f64) -> (GanZhi, GanZhi, GanZhi, GanZhi) {
+        self.sizhu_with_lon(t, None)
+    }
+
+    /// 按出生地真太阳时计算四柱
+    ///
+    /// `t`是东八区时间，`lon`为出生地经度(东经为正，弧度制)，为`None`时同[`sizhu`](SolorDate::sizhu)。
+    /// 年柱、月柱以节气交节时刻为准，与地点无关；日柱、时柱按出生地真太阳时推算
+    ///
+    /// # Example
+    /// 北京时间2023-11-11 0:30，乌鲁木齐真太阳时还是11-10 22:37，日柱、时柱都与东八区不同
+    /// ```
+    ///use rust_ephemeris::lunnar::*;
+    /// let d = SolorDate(2023, 11, 11);
+    /// let t = 0.5 / 24.0;
+    /// let sz = d.sizhu(t);
+    /// println!("{} {} {} {}", sz.0, sz.1, sz.2, sz.3); // 癸卯 癸亥 癸酉 壬子
+    /// let sz = d.sizhu_with_lon(t, Some(87.6_f64.to_radians()));
+    /// println!("{} {} {} {}", sz.0, sz.1, sz.2, sz.3); // 癸卯 癸亥 壬申 辛亥
+    /// assert_eq!(format!("{}{}", sz.2, sz.3), "壬申辛亥");
+    /// ```
+    pub fn sizhu_with_lon(&self, t: f64, lon: Option<f64>) -> (GanZhi, GanZhi, GanZhi, GanZhi) {
         let jd = JulianDate::from_day(self.0, self.1, (self.2 as f64) + t).jd;
         let y = YearCalender::from_date(self.0, self.1, (self.2 as f64) + 0.5);
 
@@ -408,6 +430,15 @@ impl SolorDate {
 
         let year_gz = GanZhi((years + 6) % 10, (years + 8) % 12);
 
+        // 日柱、时柱按真太阳时
+        let (jd, t) = match lon {
+            Some(l) => {
+                let jd = astronomy::true_solar_time(jd, -8.0, l);
+                (jd, (jd + 0.5).fract())
+            }
+            None => (jd, t),
+        };
+
         // 算日期
         let jd_floor = jd.floor() as i32; // 本日或者前一日12点的jd
         let jd_r = jd - (jd_floor as f64);
@@ -468,6 +499,146 @@ pub fn so_accurate2(jd: f64) -> f64 {
     jd_ + constants::J2000
 }
 
+/// 月相
+///
+/// 朔、上弦、望、下弦四个主要月相
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum MoonPhase {
+    #[default]
+    NewMoon, // 朔
+    FirstQuarter, // 上弦
+    FullMoon, // 望
+    LastQuarter, // 下弦
+}
+
+impl From<usize> for MoonPhase {
+    fn from(value: usize) -> Self {
+        match value % 4 {
+            0 => Self::NewMoon,
+            1 => Self::FirstQuarter,
+            2 => Self::FullMoon,
+            _ => Self::LastQuarter,
+        }
+    }
+}
+
+impl std::fmt::Display for MoonPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::NewMoon => "朔",
+            Self::FirstQuarter => "上弦",
+            Self::FullMoon => "望",
+            Self::LastQuarter => "下弦",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// 月相序数(Meeus)与布朗序数之差
+pub const BROWN_LUNATION_OFFSET: i32 = 953;
+
+/// 计算第`k`个朔望月的月相精确时刻
+///
+/// `k`为月相序数(Meeus)，`k=0`是2000-01-06的朔，布朗序数减去[`BROWN_LUNATION_OFFSET`]即为`k`。
+/// 返回东八区时间的儒略日，精度与[`so_accurate2`](crate::lunnar::so_accurate2)相同
+///
+/// # Example
+/// 计算2023年8月的望
+/// ```
+///use rust_ephemeris::lunnar::*;
+///use rust_ephemeris::JulianDate;
+/// let jd = JulianDate::from_day(2023, 8, 20.0).jd;
+/// let k = lunation_number(jd);
+/// let (y, m, d) = JulianDate::jd2day(moon_phase(k, MoonPhase::FullMoon));
+/// println!("{}-{}-{}", y, m, d); // 2023-8-31.399
+/// assert_eq!((y, m, d as i32), (2023, 8, 31));
+/// ```
+pub fn moon_phase(k: i32, phase: MoonPhase) -> f64 {
+    lunnar::moon_phase_accurate(k, phase as usize) + constants::J2000
+}
+
+/// 月相序数
+///
+/// 返回儒略日(东八区)之前最近一次朔的月相序数(Meeus)
+pub fn lunation_number(jd: f64) -> i32 {
+    lunnar::lunation_number(jd - constants::J2000)
+}
+
+/// 布朗月相序数
+///
+/// 返回儒略日(东八区)之前最近一次朔的布朗序数，1923-01-17的朔为第1个朔望月
+pub fn brown_lunation_number(jd: f64) -> i32 {
+    lunation_number(jd) + BROWN_LUNATION_OFFSET
+}
+
+/// 月相事件
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MoonPhaseEvent {
+    /// 月相
+    pub phase: MoonPhase,
+    /// 儒略日，东八区时间
+    pub jd: f64,
+    /// 月相序数(Meeus)
+    pub lunation: i32,
+}
+
+/// 月相迭代器
+///
+/// 按时间顺序迭代区间内的朔、上弦、望、下弦，通过[`moon_phases`]构造
+#[derive(Debug, Clone)]
+pub struct MoonPhases {
+    k: i32,
+    phase: usize,
+    end: f64,
+}
+
+impl Iterator for MoonPhases {
+    type Item = MoonPhaseEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let jd = lunnar::moon_phase_accurate(self.k, self.phase) + constants::J2000;
+        if jd >= self.end {
+            return None;
+        }
+        let item = MoonPhaseEvent { phase: MoonPhase::from(self.phase), jd, lunation: self.k };
+        self.phase += 1;
+        if self.phase == 4 {
+            self.phase = 0;
+            self.k += 1;
+        }
+        Some(item)
+    }
+}
+
+/// 计算区间内的月相
+///
+/// 返回`[jd0, jd1)`之间所有朔、上弦、望、下弦的迭代器, 时间为东八区儒略日
+///
+/// # Example
+/// 列出2023年8月的月相
+/// ```
+///use rust_ephemeris::lunnar::*;
+///use rust_ephemeris::JulianDate;
+/// let jd0 = JulianDate::from_day(2023, 8, 1.0).jd;
+/// let jd1 = JulianDate::from_day(2023, 9, 1.0).jd;
+/// for e in moon_phases(jd0, jd1) {
+///     let (y, m, d) = JulianDate::jd2day(e.jd);
+///     println!("{} {}-{}-{}", e.phase, y, m, d as i32);
+/// }
+/// assert_eq!(moon_phases(jd0, jd1).count(), 5); // 8月有两次望
+/// ```
+pub fn moon_phases(jd0: f64, jd1: f64) -> MoonPhases {
+    let mut r = MoonPhases { k: lunation_number(jd0), phase: 0, end: jd1 };
+    while lunnar::moon_phase_accurate(r.k, r.phase) + constants::J2000 < jd0 {
+        r.phase += 1;
+        if r.phase == 4 {
+            r.phase = 0;
+            r.k += 1;
+        }
+    }
+    r
+}
+
 /// 农历
 /// 
 /// 0: 年， 1:月， 2:日，3:是否闰月，1为闰月，否则非闰月  
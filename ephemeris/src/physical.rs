@@ -0,0 +1,225 @@
+//! 物理历表
+//!
+//! 太阳自转参数(P、B0、L0、卡林顿自转周)，火星、木星的中央经线和大红斑中天，土星光环倾角。
+//! 月面和行星视面的星等、相位等见[`moon_physical`](crate::astronomy::moon_physical)、
+//! [`planet_physical`](crate::astronomy::planet_physical)。角度均为弧度制
+
+use crate::internal::{ constants, math_utils, physical };
+use crate::astronomy::from_tt;
+use crate::time::Epoch;
+
+/// 太阳自转参数
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SunPhysical {
+    pub p: f64, // 自转轴北端的位置角，从天北极向东量
+    pub b0: f64, // 日面中心的日面纬度
+    pub l0: f64, // 日面中心的日面经度(卡林顿经度)
+    pub carrington: f64, // 卡林顿自转周数，整数部分为周序号
+}
+
+impl std::fmt::Display for SunPhysical {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "P {:.2}° B0 {:.2}° L0 {:.2}° 卡林顿周 {:.3}",
+            self.p.to_degrees(),
+            self.b0.to_degrees(),
+            self.l0.to_degrees(),
+            self.carrington
+        )
+    }
+}
+
+/// 计算太阳自转参数
+///
+/// # Arguments
+///
+/// * `jd` - 儒略日（本地时间）或[`Instant`](crate::time::Instant)
+/// * `tz` - 时区， 比如东八区`tz=-8.0`
+///
+/// # Example
+/// ```
+/// use rust_ephemeris::physical::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd = JulianDate::from_day(2023, 9, 1.0).jd;
+/// let s = sun_physical(jd, -8.0);
+/// println!("{}", s);
+/// // 9月初B0接近最大值7.25度
+/// assert!(s.b0.to_degrees() > 7.0);
+/// // L0为0时开始新的一周
+/// let start = carrington_start(s.carrington.floor() as i32 + 1, -8.0);
+/// assert!(sun_physical(start + 0.01, -8.0).l0.to_degrees() > 359.0);
+/// ```
+pub fn sun_physical(jd: impl Epoch, tz: f64) -> SunPhysical {
+    let (p, b0, l0, carrington) = physical::sun_physical(jd.tt(tz));
+    SunPhysical { p, b0, l0, carrington }
+}
+
+/// 第`n`个卡林顿自转周开始的时刻，返回本地时间儒略日，误差约0.02日(Meeus 29)
+pub fn carrington_start(n: i32, tz: f64) -> f64 {
+    let c = n as f64;
+    let m = (281.96 + 26.882476 * c).to_radians();
+    let jde =
+        2398140.227 +
+        27.2752316 * c +
+        0.1454 * m.sin() -
+        0.0085 * (2.0 * m).sin() -
+        0.0141 * (2.0 * m).cos();
+    from_tt(jde - constants::J2000, tz)
+}
+
+/// 火星视面
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MarsPhysical {
+    pub central_meridian: f64, // 中央经线，西经
+    pub de: f64, // 地心方向的火星面纬度
+    pub p: f64, // 自转轴北端的位置角
+}
+
+impl std::fmt::Display for MarsPhysical {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "中央经线 {:.2}° DE {:.2}° P {:.2}°",
+            self.central_meridian.to_degrees(),
+            self.de.to_degrees(),
+            self.p.to_degrees()
+        )
+    }
+}
+
+/// 计算火星中央经线
+///
+/// IAU自转模型，计光行时，不计相位改正
+///
+/// # Example
+/// ```
+/// use rust_ephemeris::physical::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd = JulianDate::from_day(2023, 9, 1.0).jd;
+/// let a = mars_physical(jd, -8.0);
+/// let b = mars_physical(jd + 1.0 / 24.0, -8.0);
+/// println!("{}", a);
+/// // 每小时约转14.6度
+/// let d = (b.central_meridian - a.central_meridian).to_degrees();
+/// assert!((d - 14.6).abs() < 0.1);
+/// ```
+pub fn mars_physical(jd: impl Epoch, tz: f64) -> MarsPhysical {
+    let (central_meridian, de, p) = physical::disk_orientation(3, 0, jd.tt(tz));
+    MarsPhysical { central_meridian, de, p }
+}
+
+/// 木星视面
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JupiterPhysical {
+    pub system1: f64, // 第一系统中央经线(赤道带)
+    pub system2: f64, // 第二系统中央经线(温带)
+    pub system3: f64, // 第三系统中央经线(射电)
+    pub de: f64, // 地心方向的木星面纬度
+    pub p: f64, // 自转轴北端的位置角
+}
+
+impl std::fmt::Display for JupiterPhysical {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "第一系统 {:.2}° 第二系统 {:.2}° 第三系统 {:.2}° DE {:.2}° P {:.2}°",
+            self.system1.to_degrees(),
+            self.system2.to_degrees(),
+            self.system3.to_degrees(),
+            self.de.to_degrees(),
+            self.p.to_degrees()
+        )
+    }
+}
+
+/// 计算木星中央经线
+///
+/// # Example
+/// ```
+/// use rust_ephemeris::physical::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd = JulianDate::from_day(2023, 11, 3.0).jd;
+/// let j = jupiter_physical(jd, -8.0);
+/// println!("{}", j);
+/// assert!(j.de.abs().to_degrees() < 4.0);
+/// ```
+pub fn jupiter_physical(jd: impl Epoch, tz: f64) -> JupiterPhysical {
+    let t = jd.tt(tz);
+    let (system1, de, p) = physical::disk_orientation(4, 0, t);
+    let system2 = physical::disk_orientation(4, 1, t).0;
+    let system3 = physical::disk_orientation(4, 2, t).0;
+    JupiterPhysical { system1, system2, system3, de, p }
+}
+
+/// 木星大红斑中天时刻
+///
+/// 大红斑在第二系统中缓慢漂移，需给出当前的经度。返回区间内第二系统中央经线等于`grs_lon`的时刻
+///
+/// # Arguments
+///
+/// * `jd0`, `jd1` - 起止儒略日（本地时间）或[`Instant`](crate::time::Instant)
+/// * `tz` - 时区， 比如东八区`tz=-8.0`
+/// * `grs_lon` - 大红斑的第二系统经度，弧度制
+///
+/// # Example
+/// 自转周期约9小时56分，一天2-3次中天
+/// ```
+/// use rust_ephemeris::physical::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd = JulianDate::from_day(2023, 11, 3.0).jd;
+/// let r = grs_transits(jd, jd + 1.0, -8.0, 50.0_f64.to_radians());
+/// assert!(r.len() == 2 || r.len() == 3);
+/// let d = jupiter_physical(r[0], -8.0).system2.to_degrees();
+/// assert!((d - 50.0).abs() < 0.01);
+/// ```
+pub fn grs_transits(jd0: impl Epoch, jd1: impl Epoch, tz: f64, grs_lon: f64) -> Vec<f64> {
+    let f = |t: f64| math_utils::rad2rrad(physical::disk_orientation(4, 1, t).0 - grs_lon);
+    // 跨过±180度的跳变也是变号点，只取递增的
+    math_utils::find_roots(f, jd0.tt(tz), jd1.tt(tz), 0.05)
+        .into_iter()
+        .filter(|x| x.1)
+        .map(|x| from_tt(x.0, tz))
+        .collect()
+}
+
+/// 土星光环
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SaturnRings {
+    pub b: f64, // 光环面对地球的倾角，北面为正
+    pub b_sun: f64, // 光环面对太阳的倾角
+    pub p: f64, // 光环北极的位置角
+    pub major: f64, // 光环外缘的视长轴
+    pub minor: f64, // 光环外缘的视短轴
+}
+
+impl std::fmt::Display for SaturnRings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "B {:.3}° B' {:.3}° P {:.3}° 长轴 {:.2}\" 短轴 {:.2}\"",
+            self.b.to_degrees(),
+            self.b_sun.to_degrees(),
+            self.p.to_degrees(),
+            self.major.to_degrees() * 3600.0,
+            self.minor.to_degrees() * 3600.0
+        )
+    }
+}
+
+/// 计算土星光环倾角(Meeus 45)
+///
+/// # Example
+/// 2025年3月光环侧向地球
+/// ```
+/// use rust_ephemeris::physical::*;
+/// use rust_ephemeris::JulianDate;
+/// let jd = JulianDate::from_day(2025, 3, 23.0).jd;
+/// let r = saturn_rings(jd, -8.0);
+/// println!("{}", r);
+/// assert!(r.b.to_degrees().abs() < 0.1);
+/// ```
+pub fn saturn_rings(jd: impl Epoch, tz: f64) -> SaturnRings {
+    let (b, b_sun, p, major, minor) = physical::saturn_ring_disk(jd.tt(tz));
+    SaturnRings { b, b_sun, p, major, minor }
+}
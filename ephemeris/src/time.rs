@@ -0,0 +1,224 @@
+//! 时间尺度
+//!
+//! 带时间尺度的时刻[`Instant`]，可在UTC、UT1、TAI、TT、TDB之间转换。
+//! UTC按内置闰秒表换算为TAI，闰秒表可以更新；UT1与TT之差取当前ΔT模型
+//! (见[`Options`](crate::astronomy::Options))。
+//!
+//! 以`jd: impl Epoch`和时区为参数的接口(如[`calculate_celestial_body`](crate::astronomy::calculate_celestial_body))
+//! 既可以传入`f64`本地时间的儒略日，这时当作世界时，内部再加ΔT转为力学时；也可以传入[`Instant`]，
+//! 由时刻本身的时间尺度换算，不会把力学时当作世界时，见[`Epoch`]
+
+use crate::internal::{ constants, time_scale };
+use crate::internal::lunnar::JulianDate;
+
+/// 时间尺度
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TimeScale {
+    /// 协调世界时，含闰秒，1972年以前按UT1处理
+    #[default]
+    UTC,
+    /// 世界时，与地球自转对应
+    UT1,
+    /// 国际原子时
+    TAI,
+    /// 地球时(力学时)
+    TT,
+    /// 质心力学时
+    TDB,
+}
+
+impl std::fmt::Display for TimeScale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// 带时间尺度的时刻
+///
+/// # Example
+/// ```
+/// use rust_ephemeris::time::*;
+/// // 2017年1月1日0h UTC，TT-UTC为37+32.184秒
+/// let t = Instant::from_day(2017, 1, 1.0, TimeScale::UTC);
+/// let tt = t.to(TimeScale::TT);
+/// assert!(((tt.jd() - t.jd()) * 86400.0 - 69.184).abs() < 1e-3);
+/// assert!((tt.to(TimeScale::UTC).jd() - t.jd()).abs() * 86400.0 < 1e-3);
+///
+/// // 北京时间转UTC
+/// let t = Instant::from_local(2460000.5, -8.0);
+/// assert_eq!(t.scale(), TimeScale::UTC);
+/// assert!((t.to_local(-8.0) - 2460000.5).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Instant {
+    jd: f64, // 该时间尺度下的儒略日
+    scale: TimeScale, // 时间尺度
+}
+
+impl Instant {
+    /// 由某一时间尺度下的儒略日构造
+    pub fn new(jd: f64, scale: TimeScale) -> Self {
+        Instant { jd, scale }
+    }
+
+    /// 自身时间尺度下的儒略日，其他尺度先用[`Instant::to`]转换
+    pub fn jd(&self) -> f64 {
+        self.jd
+    }
+
+    /// 时间尺度
+    pub fn scale(&self) -> TimeScale {
+        self.scale
+    }
+
+    /// 由日期构造，`d`为带小数的日
+    pub fn from_day(y: i32, m: i32, d: f64, scale: TimeScale) -> Self {
+        Instant::new(JulianDate::from_day(y, m, d).jd, scale)
+    }
+
+    /// 由本地民用时的儒略日构造UTC时刻，`tz`为时区，东八区为-8.0
+    pub fn from_local(jd: f64, tz: f64) -> Self {
+        Instant::new(jd + tz / 24.0, TimeScale::UTC)
+    }
+
+    /// 转换到其他时间尺度
+    pub fn to(&self, scale: TimeScale) -> Instant {
+        if scale == self.scale {
+            return *self;
+        }
+        let t = time_scale::from_tt(self.tt(), scale as usize);
+        Instant::new(t + constants::J2000, scale)
+    }
+
+    /// 转为本地民用时(UTC加时区)的儒略日
+    pub fn to_local(&self, tz: f64) -> f64 {
+        self.to(TimeScale::UTC).jd - tz / 24.0
+    }
+
+    /// 该时间尺度下的日期，(年, 月, 带小数的日)
+    pub fn date(&self) -> (i32, i32, f64) {
+        JulianDate::jd2day(self.jd)
+    }
+
+    // 力学时，J2000起算的日数
+    pub(crate) fn tt(&self) -> f64 {
+        time_scale::to_tt(self.jd - constants::J2000, self.scale as usize)
+    }
+}
+
+impl std::fmt::Display for Instant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // 先取整到秒，避免一天最后半秒进位成24:00:00
+        let jd = ((self.jd + 0.5) * 86400.0).round() / 86400.0 - 0.5;
+        let (y, m, d) = JulianDate::jd2day(jd);
+        let s = ((d - d.floor()) * 86400.0).round() as i64;
+        write!(
+            f,
+            "{}-{}-{} {:02}:{:02}:{:02} {}",
+            y,
+            m,
+            d.floor() as i32,
+            s / 3600,
+            (s % 3600) / 60,
+            s % 60,
+            self.scale
+        )
+    }
+}
+
+/// 时刻参数
+///
+/// 以`jd: impl Epoch`和时区`tz`为参数的接口可以传入：
+///
+/// - `f64`：本地时间的儒略日，按`tz`转为世界时，再加ΔT得到力学时
+/// - [`Instant`]：按其时间尺度换算为力学时，不受`tz`影响
+///
+/// 接口返回的时刻仍为`tz`时区的本地时间儒略日
+///
+/// # Example
+/// ```
+/// use rust_ephemeris::astronomy::*;
+/// use rust_ephemeris::time::*;
+/// let t = Instant::from_day(2023, 7, 23.5, TimeScale::TT);
+/// let a = calculate_celestial_body(CelestialBody::Moon, t, -8.0, 2.03, 0.7);
+/// let b = calculate_celestial_body(CelestialBody::Moon, t.to_local(-8.0), -8.0, 2.03, 0.7);
+/// // UTC与UT1相差不到1秒，月亮约移动0.5角秒
+/// assert!((a.a_lon - b.a_lon).abs().to_degrees() * 3600.0 < 1.0);
+///
+/// // 把力学时当作世界时会有ΔT(约69秒)的偏差，月亮约差35角秒
+/// let c = calculate_celestial_body(CelestialBody::Moon, t.jd(), 0.0, 2.03, 0.7);
+/// assert!((a.a_lon - c.a_lon).abs().to_degrees() * 3600.0 > 30.0);
+/// ```
+pub trait Epoch: Copy {
+    /// 力学时，J2000起算的日数
+    fn tt(self, tz: f64) -> f64;
+
+    /// `tz`时区本地时间的儒略日
+    fn local(self, tz: f64) -> f64;
+}
+
+impl Epoch for f64 {
+    fn tt(self, tz: f64) -> f64 {
+        crate::astronomy::to_tt(self, tz)
+    }
+
+    fn local(self, _tz: f64) -> f64 {
+        self
+    }
+}
+
+impl Epoch for Instant {
+    fn tt(self, _tz: f64) -> f64 {
+        Instant::tt(&self)
+    }
+
+    fn local(self, tz: f64) -> f64 {
+        self.to_local(tz)
+    }
+}
+
+/// TAI-UTC，单位秒，1972年以前返回`None`
+///
+/// # Argument
+/// - `jd`: UTC儒略日
+pub fn tai_utc(jd: f64) -> Option<f64> {
+    time_scale::tai_utc(jd - constants::J2000)
+}
+
+/// 当前闰秒表，(年, 月, TAI-UTC秒)，自该月1日0h UTC起生效
+pub fn leap_seconds() -> Vec<(i32, i32, f64)> {
+    time_scale::leap_seconds()
+}
+
+/// 替换闰秒表，为空时不做改变，返回是否设置成功
+pub fn set_leap_seconds(table: Vec<(i32, i32, f64)>) -> bool {
+    time_scale::set_leap_seconds(table)
+}
+
+/// 增加一个正闰秒
+///
+/// 闰秒加在前一个月的最后一分钟，自`y`年`m`月1日0h UTC起TAI-UTC增加1秒。
+/// 日期须晚于表中最后一项，否则返回`false`
+///
+/// # Example
+/// ```
+/// use rust_ephemeris::time::*;
+/// let t = Instant::from_day(2031, 1, 1.0, TimeScale::UTC);
+/// assert_eq!(tai_utc(t.jd()), Some(37.0));
+/// assert!(add_leap_second(2030, 7));
+/// assert_eq!(tai_utc(t.jd()), Some(38.0));
+/// assert!(!add_leap_second(2030, 1));
+/// ```
+pub fn add_leap_second(y: i32, m: i32) -> bool {
+    time_scale::add_leap_second(y, m)
+}
+
+#[test]
+fn test_instant_display() {
+    let t = Instant::from_day(2023, 12, 31.0 + (86399.7 / 86400.0), TimeScale::UTC);
+    assert_eq!(t.to_string(), "2024-1-1 00:00:00 UTC");
+    let t = Instant::from_day(2023, 12, 31.0 + (86399.2 / 86400.0), TimeScale::TT);
+    assert_eq!(t.to_string(), "2023-12-31 23:59:59 TT");
+    let t = Instant::from_day(2023, 12, 31.5, TimeScale::UTC);
+    assert_eq!(t.to_string(), "2023-12-31 12:00:00 UTC");
+}